@@ -0,0 +1,48 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::env;
+use std::process::Command;
+
+/// Sets environment variables that describe the compiler, for use by the
+/// `since` and `nightly` attributes.
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let output = match Command::new(rustc).arg("-vV").output() {
+        Ok(output) if output.status.success() => output,
+        _ => return,
+    };
+    let output = String::from_utf8_lossy(&output.stdout);
+    let release =
+        match output.lines().find_map(|l| l.strip_prefix("release: ")) {
+            Some(release) => release.trim(),
+            None => return,
+        };
+
+    // Releases look like `1.61.0`, `1.62.0-beta.3`, or `1.63.0-nightly`.
+    let mut parts = release.splitn(2, '-');
+    let version = parts.next().unwrap_or_default();
+    let channel = match parts.next() {
+        Some(suffix) => suffix.split('.').next().unwrap_or_default(),
+        None => "stable",
+    };
+    println!("cargo:rustc-env=ADD_SYNTAX_RUSTC_VERSION={}", version);
+    println!("cargo:rustc-env=ADD_SYNTAX_RUSTC_CHANNEL={}", channel);
+}
@@ -0,0 +1,102 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use crate::item::{is_punct, map_group, split_attrs, split_commas, trees_eq};
+use proc_macro::{Delimiter, Span, TokenStream, TokenTree};
+
+/// Parses the comma-separated attribute paths provided to `strip_attrs`.
+fn parse_paths(attr: TokenStream) -> Result<Vec<Vec<TokenTree>>> {
+    let trees: Vec<_> = attr.into_iter().collect();
+    let paths: Vec<_> =
+        split_commas(&trees).into_iter().map(|path| path.to_vec()).collect();
+    if paths.is_empty() {
+        return Err(Error::new(Span::call_site(), "expected attribute paths"));
+    }
+    Ok(paths)
+}
+
+/// Returns whether the contents of an attribute, like `inline(always)`,
+/// start with one of `paths`.
+fn has_path(attr: &TokenStream, paths: &[Vec<TokenTree>]) -> bool {
+    let trees: Vec<_> = attr.clone().into_iter().collect();
+    // The path ends at the arguments, like `(always)` or `= "..."`.
+    let len = trees
+        .iter()
+        .position(|t| matches!(t, TokenTree::Group(_)) || is_punct(t, '='))
+        .unwrap_or(trees.len());
+    paths.iter().any(|p| trees_eq(p, &trees[..len]))
+}
+
+/// Removes the attributes in `tokens` whose paths are in `paths`. If
+/// `recursive` is true, attributes in groups are removed too; otherwise,
+/// `tokens` should consist only of outer attributes.
+fn strip(
+    tokens: TokenStream,
+    paths: &[Vec<TokenTree>],
+    recursive: bool,
+) -> TokenStream {
+    let trees: Vec<_> = tokens.into_iter().collect();
+    let mut result = TokenStream::new();
+    let mut i = 0;
+    while let Some(tree) = trees.get(i) {
+        // Inner attributes have a `!` between the `#` and the brackets.
+        let bang = trees.get(i + 1).map_or(false, |t| is_punct(t, '!'));
+        let len = if bang {
+            3
+        } else {
+            2
+        };
+        if let (true, Some(TokenTree::Group(g))) =
+            (is_punct(tree, '#'), trees.get(i + len - 1))
+        {
+            if g.delimiter() == Delimiter::Bracket
+                && has_path(&g.stream(), paths)
+            {
+                i += len;
+                continue;
+            }
+        }
+        i += 1;
+        result.extend([match tree {
+            TokenTree::Group(g) if recursive => {
+                map_group(g, |s| strip(s, paths, recursive)).into()
+            }
+            tree => tree.clone(),
+        }]);
+    }
+    result
+}
+
+pub fn strip_attrs(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let paths = parse_paths(attr)?;
+    let (attrs, rest) = split_attrs(item);
+    let mut tokens = strip(attrs, &paths, false);
+    tokens.extend(rest);
+    Ok(tokens)
+}
+
+pub fn strip_attrs_recursive(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    Ok(strip(item, &parse_paths(attr)?, true))
+}
@@ -0,0 +1,329 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result, expect_empty};
+use crate::item::{Item, split_inner_attrs};
+use crate::item::{is_ident, is_punct, map_group, skip_generics};
+use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span};
+use proc_macro::{TokenStream, TokenTree};
+
+/// Keywords that start statements that end with a block, like `if` and
+/// `fn`. Such statements don't need a terminating semicolon.
+const BLOCK_KEYWORDS: &[&str] = &[
+    "if",
+    "match",
+    "loop",
+    "while",
+    "for",
+    "unsafe",
+    "async",
+    "fn",
+    "struct",
+    "enum",
+    "union",
+    "trait",
+    "impl",
+    "mod",
+    "extern",
+    "macro_rules",
+];
+
+fn is_brace_group(tree: &TokenTree) -> bool {
+    matches!(tree, TokenTree::Group(g) if g.delimiter() == Delimiter::Brace)
+}
+
+/// Returns the braced body of a `mod`, `impl`, `trait`, or `extern` block.
+pub fn block_body(item: &mut Item) -> Result<&mut Group> {
+    let span = item.span();
+    if !matches!(
+        item.kind().as_deref(),
+        Some("mod" | "impl" | "trait" | "extern")
+    ) {
+        return Err(Error::new(
+            span,
+            "expected a `mod`, `impl`, `trait`, or `extern` block",
+        ));
+    }
+    match item.rest.last_mut() {
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => {
+            Ok(g)
+        }
+        _ => Err(Error::new(span, "expected a braced body")),
+    }
+}
+
+pub fn prepend_inner(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let mut item = Item::parse(item);
+    let body = block_body(&mut item)?;
+    *body = map_group(body, |stream| {
+        let (mut tokens, rest) = split_inner_attrs(stream);
+        tokens.extend(attr.into_iter().chain(rest));
+        tokens
+    });
+    Ok(item.into_tokens())
+}
+
+pub fn append_inner(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let mut item = Item::parse(item);
+    let body = block_body(&mut item)?;
+    *body = map_group(body, |mut stream| {
+        stream.extend(attr);
+        stream
+    });
+    Ok(item.into_tokens())
+}
+
+/// Returns the braced body of a function.
+fn fn_body(item: &mut Item) -> Result<&mut Group> {
+    let span = item.span();
+    if item.kind().as_deref() != Some("fn") {
+        return Err(Error::new(span, "expected a function"));
+    }
+    match item.rest.last_mut() {
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => {
+            Ok(g)
+        }
+        _ => Err(Error::new(span, "expected a function with a body")),
+    }
+}
+
+/// Replaces the body of a function, or the semicolon in place of one, with
+/// `body`.
+fn set_fn_body(item: &mut Item, body: Group) -> Result<()> {
+    let span = item.span();
+    if item.kind().as_deref() != Some("fn") {
+        return Err(Error::new(span, "expected a function"));
+    }
+    match item.rest.last_mut() {
+        Some(tree) if is_brace_group(tree) || is_punct(tree, ';') => {
+            *tree = body.into();
+            Ok(())
+        }
+        _ => Err(Error::new(span, "expected a function body or `;`")),
+    }
+}
+
+/// Returns whether a function has an explicit return type.
+fn has_return_type(item: &Item) -> bool {
+    let params = skip_generics(&item.rest, 2);
+    match item.rest.get(params + 1..params + 3) {
+        Some([arrow1, arrow2]) => {
+            is_punct(arrow1, '-') && is_punct(arrow2, '>')
+        }
+        _ => false,
+    }
+}
+
+/// Returns the number of trees occupied by the statement at the start of
+/// `trees` (which should not contain inner attributes).
+fn statement_len(trees: &[TokenTree]) -> usize {
+    let mut i = 0;
+    // Skip outer attributes.
+    while trees.get(i).map_or(false, |t| is_punct(t, '#')) {
+        i += 2;
+    }
+    // Skip labels, like `'outer:`.
+    if trees.get(i).map_or(false, |t| is_punct(t, '\'')) {
+        i += 3;
+    }
+
+    // Check for macro invocations with braces, like `thread_local! {}`.
+    let mut path_end = i;
+    while trees.get(path_end).map_or(false, |t| {
+        matches!(t, TokenTree::Ident(_)) || is_punct(t, ':')
+    }) {
+        path_end += 1;
+    }
+    if let Some([bang, body]) = trees.get(path_end..path_end + 2) {
+        if path_end > i && is_punct(bang, '!') && is_brace_group(body) {
+            let end = path_end + 2;
+            match trees.get(end) {
+                // The invocation is part of a larger expression, which ends
+                // at the next semicolon like other expressions.
+                Some(t) if is_punct(t, '.') || is_punct(t, '?') => {}
+                Some(t) if is_punct(t, ';') => return end + 1,
+                _ => return end,
+            }
+        }
+    }
+
+    let mut block_like = match trees.get(i) {
+        Some(t @ TokenTree::Ident(_)) if is_ident(t, "const") => {
+            // `const` blocks and functions, but not constants.
+            trees.get(i + 1).map_or(false, |t| {
+                is_brace_group(t)
+                    || ["fn", "unsafe", "async", "extern"]
+                        .iter()
+                        .any(|k| is_ident(t, k))
+            })
+        }
+        Some(t) => {
+            is_brace_group(t) || BLOCK_KEYWORDS.iter().any(|k| is_ident(t, k))
+        }
+        None => false,
+    };
+
+    while let Some(tree) = trees.get(i) {
+        i += 1;
+        if is_punct(tree, ';') {
+            return i;
+        }
+        if !(block_like && is_brace_group(tree)) {
+            continue;
+        }
+        match trees.get(i) {
+            // `else` continues an `if` expression.
+            Some(t) if is_ident(t, "else") => i += 1,
+            // The block is part of a larger expression.
+            Some(t) if is_punct(t, '.') || is_punct(t, '?') => {
+                block_like = false;
+            }
+            Some(t) if is_punct(t, ';') => return i + 1,
+            _ => return i,
+        }
+    }
+    i
+}
+
+/// Returns the index of the tail expression in `trees` (the contents of a
+/// block without its inner attributes), if there is one.
+fn tail_start(trees: &[TokenTree]) -> Option<usize> {
+    let mut start = 0;
+    while start < trees.len() {
+        let len = statement_len(&trees[start..]);
+        if start + len >= trees.len() {
+            return match trees.last() {
+                Some(t) if is_punct(t, ';') => None,
+                _ => Some(start),
+            };
+        }
+        start += len;
+    }
+    None
+}
+
+/// Adds a semicolon to the end of a sequence of statements if needed.
+fn terminate(stmts: &mut Vec<TokenTree>) {
+    if let Some(last) = stmts.last() {
+        if !(is_punct(last, ';') || is_brace_group(last)) {
+            stmts.push(Punct::new(';', Spacing::Alone).into());
+        }
+    }
+}
+
+pub fn prepend_stmts(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let mut item = Item::parse(item);
+    let body = fn_body(&mut item)?;
+    let mut stmts: Vec<_> = attr.into_iter().collect();
+    terminate(&mut stmts);
+    *body = map_group(body, |stream| {
+        let (mut tokens, rest) = split_inner_attrs(stream);
+        tokens.extend(stmts.into_iter().chain(rest));
+        tokens
+    });
+    Ok(item.into_tokens())
+}
+
+pub fn append_stmts(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let mut item = Item::parse(item);
+    let returns = has_return_type(&item);
+    let body = fn_body(&mut item)?;
+    let mut stmts: Vec<_> = attr.into_iter().collect();
+    terminate(&mut stmts);
+    *body = map_group(body, |stream| {
+        let (mut tokens, rest) = split_inner_attrs(stream);
+        let mut trees: Vec<_> = rest.into_iter().collect();
+        match tail_start(&trees) {
+            Some(start) if returns => {
+                trees.splice(start..start, stmts);
+            }
+            // Functions without a return type are treated as though they
+            // have no tail expression, so the statements run last.
+            tail => {
+                if tail.is_some() {
+                    terminate(&mut trees);
+                }
+                trees.extend(stmts);
+            }
+        }
+        tokens.extend(trees);
+        tokens
+    });
+    Ok(item.into_tokens())
+}
+
+pub fn unsafe_body(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    expect_empty(attr)?;
+    let mut item = Item::parse(item);
+    let body = fn_body(&mut item)?;
+    *body = map_group(body, |stream| {
+        let (mut tokens, rest) = split_inner_attrs(stream);
+        tokens.extend([
+            TokenTree::from(Ident::new("unsafe", Span::call_site())),
+            Group::new(Delimiter::Brace, rest).into(),
+        ]);
+        tokens
+    });
+    Ok(item.into_tokens())
+}
+
+pub fn replace_body(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let mut trees: Vec<_> = attr.into_iter().collect();
+    let body = match trees.pop() {
+        Some(TokenTree::Group(g))
+            if trees.is_empty() && g.delimiter() == Delimiter::Brace =>
+        {
+            g
+        }
+        last => {
+            trees.extend(last);
+            Group::new(Delimiter::Brace, trees.into_iter().collect())
+        }
+    };
+    let mut item = Item::parse(item);
+    set_fn_body(&mut item, body)?;
+    Ok(item.into_tokens())
+}
+
+pub fn stub(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    expect_empty(attr)?;
+    let mut item = Item::parse(item);
+    let body = "::core::unimplemented!()".parse().unwrap();
+    set_fn_body(&mut item, Group::new(Delimiter::Brace, body))?;
+    // The function's parameters are no longer used.
+    item.attrs.extend("#[allow(unused_variables)]".parse::<TokenStream>());
+    Ok(item.into_tokens())
+}
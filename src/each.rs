@@ -0,0 +1,159 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::body::block_body;
+use crate::error::{Error, Result};
+use crate::item::{Item, find_top_level, is_ident, is_punct, map_group};
+use crate::item::{split_commas, split_inner_attrs};
+use crate::op::Op;
+use proc_macro::{Delimiter, Span, TokenStream, TokenTree};
+
+/// Item kinds that can be used as filters.
+const KINDS: &[&str] = &[
+    "fn", "struct", "enum", "union", "trait", "type", "const", "static",
+    "mod", "impl", "use", "extern",
+];
+
+/// Returns whether `name` matches `pattern`, in which `*` matches any
+/// sequence of characters and `?` matches any single character.
+fn glob_match(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (Some(('*', rest)), _) => {
+            glob_match(rest, name)
+                || !name.is_empty() && glob_match(pattern, &name[1..])
+        }
+        (Some((p, p_rest)), Some((n, n_rest))) => {
+            (*p == '?' || p == n) && glob_match(p_rest, n_rest)
+        }
+        (p, n) => p.is_none() && n.is_none(),
+    }
+}
+
+/// The arguments to [`each`].
+struct Args {
+    kinds: Vec<String>,
+    names: Vec<Vec<char>>,
+    op: Op,
+}
+
+impl Args {
+    fn parse(attr: TokenStream) -> Result<Self> {
+        let trees: Vec<_> = attr.into_iter().collect();
+        let mut segments = split_commas(&trees);
+        let op = segments.pop().ok_or_else(|| {
+            Error::new(Span::call_site(), "expected an operation")
+        })?;
+        let op = Op::parse(op)?;
+
+        let mut args = Self {
+            kinds: Vec::new(),
+            names: Vec::new(),
+            op,
+        };
+        for segment in segments {
+            match segment {
+                [TokenTree::Ident(i)] if KINDS.contains(&&*i.to_string()) => {
+                    args.kinds.push(i.to_string());
+                }
+                [name, eq, TokenTree::Literal(lit)]
+                    if is_ident(name, "name") && is_punct(eq, '=') =>
+                {
+                    let pattern = lit.to_string();
+                    if !pattern.starts_with('"') {
+                        return Err(Error::new(
+                            lit.span(),
+                            "expected a string literal",
+                        ));
+                    }
+                    args.names
+                        .push(pattern.trim_matches('"').chars().collect());
+                }
+                _ => {
+                    return Err(Error::new(
+                        segment[0].span(),
+                        "expected an item kind or `name = \"...\"`",
+                    ));
+                }
+            }
+        }
+        Ok(args)
+    }
+
+    /// Returns whether the operation should be applied to `item`.
+    fn matches(&self, item: &Item) -> bool {
+        let kind = item.kind().unwrap_or_default();
+        if !(self.kinds.is_empty() || self.kinds.contains(&kind)) {
+            return false;
+        }
+        if self.names.is_empty() {
+            return true;
+        }
+        item.name_index().map_or(false, |i| {
+            let name: Vec<_> = item.rest[i].to_string().chars().collect();
+            self.names.iter().any(|p| glob_match(p, &name))
+        })
+    }
+}
+
+/// Returns the number of trees occupied by the item at the start of `trees`.
+fn item_len(trees: &[TokenTree]) -> usize {
+    let item = Item::parse(trees.iter().cloned().collect());
+    let start = trees.len() - item.rest.len();
+    // Items like constants end with the first semicolon, even if they
+    // contain a braced expression. Their initializers can contain `<` as an
+    // operator, so angle brackets aren't tracked.
+    if matches!(
+        item.kind().as_deref(),
+        Some("const" | "static" | "type" | "use")
+    ) {
+        return trees[start..]
+            .iter()
+            .position(|t| is_punct(t, ';'))
+            .map_or(trees.len(), |i| start + i + 1);
+    }
+    // Other items end with a semicolon or a braced body.
+    find_top_level(trees, start, |t| match t {
+        TokenTree::Group(g) => g.delimiter() == Delimiter::Brace,
+        t => is_punct(t, ';'),
+    })
+    .map_or(trees.len(), |i| i + 1)
+}
+
+pub fn each(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let args = Args::parse(attr)?;
+    let mut item = Item::parse(item);
+    let body = block_body(&mut item)?;
+    *body = map_group(body, |stream| {
+        let (mut tokens, rest) = split_inner_attrs(stream);
+        let trees: Vec<_> = rest.into_iter().collect();
+        let mut i = 0;
+        while i < trees.len() {
+            let len = item_len(&trees[i..]);
+            let inner: TokenStream =
+                trees[i..i + len].iter().cloned().collect();
+            i += len;
+            if args.matches(&Item::parse(inner.clone())) {
+                tokens.extend(args.op.apply(inner));
+            } else {
+                tokens.extend(inner);
+            }
+        }
+        tokens
+    });
+    Ok(item.into_tokens())
+}
@@ -0,0 +1,72 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span};
+use proc_macro::{TokenStream, TokenTree};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// An error that is reported with [`compile_error!`].
+pub struct Error {
+    span: Span,
+    message: String,
+}
+
+impl Error {
+    pub fn new(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+        }
+    }
+
+    /// Converts this error into an invocation of [`compile_error!`].
+    pub fn into_compile_error(self) -> TokenStream {
+        let span = self.span;
+        let punct = |c, spacing| {
+            let mut punct = Punct::new(c, spacing);
+            punct.set_span(span);
+            TokenTree::from(punct)
+        };
+        let mut message = Literal::string(&self.message);
+        message.set_span(span);
+        let mut body =
+            Group::new(Delimiter::Brace, TokenTree::from(message).into());
+        body.set_span(span);
+        let mut tokens = TokenStream::new();
+        tokens.extend([
+            punct(':', Spacing::Joint),
+            punct(':', Spacing::Alone),
+            Ident::new("core", span).into(),
+            punct(':', Spacing::Joint),
+            punct(':', Spacing::Alone),
+            Ident::new("compile_error", span).into(),
+            punct('!', Spacing::Alone),
+            body.into(),
+        ]);
+        tokens
+    }
+}
+
+/// Returns an error if `tokens` isn't empty.
+pub fn expect_empty(tokens: TokenStream) -> Result<()> {
+    match tokens.into_iter().next() {
+        Some(tree) => Err(Error::new(tree.span(), "unexpected token")),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,157 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use crate::item::{Item, append_list, is_punct, map_group, prepend_list};
+use crate::item::{skip_generics, split_attrs, split_commas, split_vis};
+use proc_macro::{Delimiter, Punct, Spacing, TokenStream, TokenTree};
+
+/// Returns the index in [`Item::rest`] of the group containing the fields of
+/// a struct or union, or the variants of an enum.
+fn members_index(item: &Item, variants: bool) -> Result<usize> {
+    let kind = item.kind();
+    let (valid, message) = if variants {
+        (kind.as_deref() == Some("enum"), "expected an enum")
+    } else {
+        (
+            matches!(kind.as_deref(), Some("struct" | "union")),
+            "expected a struct or union",
+        )
+    };
+    if !valid {
+        return Err(Error::new(item.span(), message));
+    }
+    let last = item.rest.len() - 1;
+    let index = match item.rest.get(last) {
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => last,
+        // Tuple structs.
+        _ => skip_generics(&item.rest, item.generics_index().unwrap()),
+    };
+    match item.rest.get(index) {
+        Some(TokenTree::Group(_)) => Ok(index),
+        _ => Err(Error::new(item.span(), "expected a struct with fields")),
+    }
+}
+
+fn map_members(
+    item: TokenStream,
+    variants: bool,
+    f: impl FnOnce(&mut Vec<TokenTree>),
+) -> Result<TokenStream> {
+    let mut item = Item::parse(item);
+    let index = members_index(&item, variants)?;
+    if let TokenTree::Group(g) = &item.rest[index] {
+        let group = map_group(g, |stream| {
+            let mut members: Vec<_> = stream.into_iter().collect();
+            f(&mut members);
+            members.into_iter().collect()
+        });
+        item.rest[index] = group.into();
+    }
+    Ok(item.into_tokens())
+}
+
+pub fn prepend_fields(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    map_members(item, false, |fields| prepend_list(fields, attr))
+}
+
+pub fn append_fields(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    map_members(item, false, |fields| append_list(fields, attr))
+}
+
+pub fn prepend_variants(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    map_members(item, true, |variants| prepend_list(variants, attr))
+}
+
+pub fn append_variants(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    map_members(item, true, |variants| append_list(variants, attr))
+}
+
+pub fn fields_vis(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let (vis, rest) = split_vis(attr);
+    let rest: Vec<_> = rest.into_iter().collect();
+    let names = match rest.split_first() {
+        Some((comma, names)) if is_punct(comma, ',') => split_commas(names),
+        Some((tree, _)) => {
+            return Err(Error::new(tree.span(), "expected a visibility"));
+        }
+        None => Vec::new(),
+    };
+    let names = names
+        .into_iter()
+        .map(|name| match name {
+            [name] => Ok(name),
+            _ => Err(Error::new(name[1].span(), "expected `,`")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut item = Item::parse(item);
+    let index = members_index(&item, false)?;
+    let group = match &item.rest[index] {
+        TokenTree::Group(g) => g.clone(),
+        _ => unreachable!(),
+    };
+    let named = group.delimiter() == Delimiter::Brace;
+    let fields: Vec<_> = group.stream().into_iter().collect();
+    let mut found = vec![false; names.len()];
+    let mut tokens = TokenStream::new();
+    for (i, field) in split_commas(&fields).into_iter().enumerate() {
+        let (attrs, rest) = split_attrs(field.iter().cloned().collect());
+        let (old_vis, rest) = split_vis(rest);
+        let rest: Vec<_> = rest.into_iter().collect();
+        let name = if named {
+            rest[0].to_string()
+        } else {
+            i.to_string()
+        };
+        let position = names.iter().position(|n| n.to_string() == name);
+        if let Some(p) = position {
+            found[p] = true;
+        }
+        tokens.extend(attrs);
+        if names.is_empty() || position.is_some() {
+            tokens.extend(vis.clone());
+        } else {
+            tokens.extend(old_vis);
+        }
+        tokens.extend(rest);
+        tokens.extend([TokenTree::from(Punct::new(',', Spacing::Alone))]);
+    }
+    if let Some(p) = found.iter().position(|f| !f) {
+        let name = names[p];
+        let message = format!("no field named `{}`", name);
+        return Err(Error::new(name.span(), message));
+    }
+    item.rest[index] = map_group(&group, |_| tokens).into();
+    Ok(item.into_tokens())
+}
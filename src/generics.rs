@@ -0,0 +1,160 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use crate::item::{Item, find_top_level, is_ident, is_punct};
+use crate::item::{skip_generics, split_commas};
+use proc_macro::{Delimiter, Ident, Punct, Spacing, Span};
+use proc_macro::{TokenStream, TokenTree};
+use std::ops::Range;
+
+/// Returns the trees inside the angle brackets that enclose `trees`, or all
+/// of `trees` if it isn't enclosed in angle brackets.
+fn strip_angle_brackets(trees: &[TokenTree]) -> &[TokenTree] {
+    if trees.len() >= 2 && skip_generics(trees, 0) == trees.len() {
+        &trees[1..trees.len() - 1]
+    } else {
+        trees
+    }
+}
+
+/// Returns whether a generic parameter is a lifetime.
+fn is_lifetime_param(param: &[TokenTree]) -> bool {
+    let mut i = 0;
+    // Skip attributes, like `#[may_dangle]`.
+    while param.get(i).map_or(false, |t| is_punct(t, '#')) {
+        i += 2;
+    }
+    param.get(i).map_or(false, |t| is_punct(t, '\''))
+}
+
+/// Returns the index in [`Item::rest`] where an item's generic parameters
+/// start (or would start), or an error if it can't have them.
+fn generics_index(item: &Item) -> Result<usize> {
+    item.generics_index().ok_or_else(|| {
+        Error::new(
+            item.span(),
+            "expected a function, struct, enum, union, trait, type alias, or \
+            impl",
+        )
+    })
+}
+
+/// Returns the range in [`Item::rest`] of an item's generic parameters,
+/// including the angle brackets. If the item has no generic parameters, the
+/// range is empty and starts where they would be added.
+pub fn generics_range(item: &Item) -> Result<Range<usize>> {
+    let start = generics_index(item)?;
+    Ok(start..skip_generics(&item.rest, start))
+}
+
+/// Returns the range in [`Item::rest`] of an item's `where` clause. If the
+/// item has no `where` clause, the range is empty and starts where one would
+/// be added.
+pub fn where_range(item: &Item) -> Result<Range<usize>> {
+    let start = generics_range(item)?.end;
+    let rest = &item.rest;
+    // The `where` clause precedes the body, the `;`, or the `=` in a type
+    // alias.
+    let is_alias = item.kind().as_deref() == Some("type");
+    let end = find_top_level(rest, start, |t| is_alias && is_punct(t, '='))
+        .unwrap_or_else(|| rest.len().saturating_sub(1));
+    let start = rest[start..end]
+        .iter()
+        .position(|t| is_ident(t, "where"))
+        .map_or(end, |i| start + i);
+    Ok(start..end)
+}
+
+pub fn add_generics(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let added: Vec<_> = attr.into_iter().collect();
+    let mut item = Item::parse(item);
+    let range = generics_range(&item)?;
+    let (start, end) = (range.start, range.end);
+    let existing = if end > start {
+        &item.rest[start + 1..end - 1]
+    } else {
+        &[]
+    };
+
+    let mut params = split_commas(existing);
+    params.extend(split_commas(strip_angle_brackets(&added)));
+    // Lifetimes must precede other parameters.
+    params.sort_by_key(|p| !is_lifetime_param(p));
+    let mut generics = vec![Punct::new('<', Spacing::Alone).into()];
+    for param in params {
+        generics.extend(param.iter().cloned());
+        generics.push(Punct::new(',', Spacing::Alone).into());
+    }
+    generics.push(TokenTree::from(Punct::new('>', Spacing::Alone)));
+    item.rest.splice(start..end, generics);
+    Ok(item.into_tokens())
+}
+
+pub fn add_where(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let mut preds: Vec<_> = attr.into_iter().collect();
+    if preds.is_empty() {
+        return Err(Error::new(Span::call_site(), "expected predicates"));
+    }
+    let mut item = Item::parse(item);
+    let range = where_range(&item)?;
+    let last = &item.rest[range.end.saturating_sub(1)];
+    if range.is_empty() {
+        preds.insert(0, Ident::new("where", Span::call_site()).into());
+    } else if !(is_ident(last, "where") || is_punct(last, ',')) {
+        preds.insert(0, Punct::new(',', Spacing::Alone).into());
+    }
+    item.rest.splice(range.end..range.end, preds);
+    Ok(item.into_tokens())
+}
+
+pub fn add_supertraits(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let mut bounds: Vec<_> = attr.into_iter().collect();
+    if bounds.is_empty() {
+        return Err(Error::new(Span::call_site(), "expected bounds"));
+    }
+    let mut item = Item::parse(item);
+    if item.kind().as_deref() != Some("trait") {
+        return Err(Error::new(item.span(), "expected a trait"));
+    }
+    let start = skip_generics(&item.rest, generics_index(&item)?);
+    let rest = &item.rest;
+    if !rest.get(start).map_or(false, |t| is_punct(t, ':')) {
+        bounds.insert(0, Punct::new(':', Spacing::Alone).into());
+        item.rest.splice(start..start, bounds);
+        return Ok(item.into_tokens());
+    }
+
+    // The existing bounds end at the `where` clause or the body.
+    let end = find_top_level(rest, start + 1, |t| match t {
+        TokenTree::Group(g) => g.delimiter() == Delimiter::Brace,
+        t => is_ident(t, "where"),
+    })
+    .unwrap_or(rest.len());
+    if !(end == start + 1 || is_punct(&rest[end - 1], '+')) {
+        bounds.insert(0, Punct::new('+', Spacing::Alone).into());
+    }
+    item.rest.splice(end..end, bounds);
+    Ok(item.into_tokens())
+}
@@ -0,0 +1,99 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use crate::item::{Item, is_ident, is_punct, split_vis, trees_eq};
+use proc_macro::{Span, TokenStream, TokenTree};
+
+/// Returns the number of trees at the start of `trees` (an item without its
+/// attributes and visibility) that precede the item's generics, parameters,
+/// type, or body. This includes the item's qualifiers, keyword, and name.
+fn head_len(trees: &[TokenTree]) -> usize {
+    trees
+        .iter()
+        .position(|t| {
+            matches!(t, TokenTree::Group(_))
+                || ['<', ':', '=', ';'].iter().any(|c| is_punct(t, *c))
+        })
+        .unwrap_or(trees.len())
+}
+
+pub fn remove(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let target: Vec<_> = attr.into_iter().collect();
+    let first = target.first().ok_or_else(|| {
+        Error::new(Span::call_site(), "expected tokens to remove")
+    })?;
+    let not_found = || {
+        let tokens: TokenStream = target.iter().cloned().collect();
+        Error::new(first.span(), format!("`{}` not found in item", tokens))
+    };
+
+    let item = Item::parse(item);
+    let mut tokens = item.attrs;
+    let vis: Vec<_> = item.vis.into_iter().collect();
+    if is_ident(first, "pub") || is_ident(first, "crate") {
+        // `pub` also removes visibilities like `pub(crate)`.
+        if !(trees_eq(&target, &vis)
+            || (!vis.is_empty() && trees_eq(&target, &vis[..1])))
+        {
+            return Err(not_found());
+        }
+        tokens.extend(item.quals.into_tokens().chain(item.rest));
+        return Ok(tokens);
+    }
+
+    let mut trees: Vec<_> =
+        item.quals.into_tokens().chain(item.rest).collect();
+    let start = trees[..head_len(&trees)]
+        .windows(target.len())
+        .position(|w| trees_eq(w, &target))
+        .ok_or_else(not_found)?;
+    trees.drain(start..start + target.len());
+    tokens.extend(vis.into_iter().chain(trees));
+    Ok(tokens)
+}
+
+pub fn set_vis(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let (vis, rest) = split_vis(attr);
+    if let Some(tree) = rest.into_iter().next() {
+        return Err(Error::new(tree.span(), "expected a visibility"));
+    }
+    let mut item = Item::parse(item);
+    item.vis = vis;
+    Ok(item.into_tokens())
+}
+
+pub fn rename(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let mut trees = attr.into_iter();
+    let name = match (trees.next(), trees.next()) {
+        (Some(name @ TokenTree::Ident(_)), None) => name,
+        (_, Some(tree)) => {
+            return Err(Error::new(tree.span(), "unexpected token"));
+        }
+        (tree, None) => {
+            let span = tree.map_or_else(Span::call_site, |t| t.span());
+            return Err(Error::new(span, "expected an identifier"));
+        }
+    };
+    let mut item = Item::parse(item);
+    let index = item.name_index().ok_or_else(|| {
+        Error::new(item.span(), "expected an item with a name")
+    })?;
+    item.rest[index] = name;
+    Ok(item.into_tokens())
+}
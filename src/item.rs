@@ -0,0 +1,306 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::qualifiers::Qualifiers;
+use proc_macro::{Delimiter, Group, Punct, Spacing, Span};
+use proc_macro::{TokenStream, TokenTree};
+
+/// Returns whether `tree` is the identifier `ident`.
+pub fn is_ident(tree: &TokenTree, ident: &str) -> bool {
+    matches!(tree, TokenTree::Ident(i) if i.to_string() == ident)
+}
+
+/// Returns whether `tree` is the punctuation character `c`.
+pub fn is_punct(tree: &TokenTree, c: char) -> bool {
+    matches!(tree, TokenTree::Punct(p) if p.as_char() == c)
+}
+
+/// Returns the index just past the generic parameters or arguments that
+/// start at `trees[start]`, or `start` if `trees[start]` isn't `<`.
+pub fn skip_generics(trees: &[TokenTree], start: usize) -> usize {
+    if !trees.get(start).map_or(false, |t| is_punct(t, '<')) {
+        return start;
+    }
+    let mut depth = 0_usize;
+    for (i, tree) in trees.iter().enumerate().skip(start) {
+        if is_punct(tree, '<') {
+            depth += 1;
+        } else if is_punct(tree, '>') {
+            // The `>` in `->` doesn't close a bracket.
+            if is_punct(&trees[i - 1], '-') {
+                continue;
+            }
+            depth -= 1;
+            if depth == 0 {
+                return i + 1;
+            }
+        }
+    }
+    trees.len()
+}
+
+/// Returns the index of the first tree in `trees[start..]` that isn't inside
+/// angle brackets and satisfies `f`.
+pub fn find_top_level(
+    trees: &[TokenTree],
+    start: usize,
+    f: impl Fn(&TokenTree) -> bool,
+) -> Option<usize> {
+    let mut depth = 0_usize;
+    for (i, tree) in trees.iter().enumerate().skip(start) {
+        if depth == 0 && f(tree) {
+            return Some(i);
+        }
+        if is_punct(tree, '<') {
+            depth += 1;
+        } else if is_punct(tree, '>') {
+            // The `>` in `->` doesn't close a bracket.
+            if i == 0 || !is_punct(&trees[i - 1], '-') {
+                depth = depth.saturating_sub(1);
+            }
+        }
+    }
+    None
+}
+
+/// Splits `trees` at commas that aren't inside angle brackets. Empty
+/// segments (like the one after a trailing comma) are omitted.
+pub fn split_commas(trees: &[TokenTree]) -> Vec<&[TokenTree]> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    while let Some(i) = find_top_level(trees, start, |t| is_punct(t, ',')) {
+        segments.push(&trees[start..i]);
+        start = i + 1;
+    }
+    segments.push(&trees[start..]);
+    segments.retain(|s| !s.is_empty());
+    segments
+}
+
+/// Adds the comma-separated elements in `added` to the end of the
+/// comma-separated list `list`.
+pub fn append_list(list: &mut Vec<TokenTree>, added: TokenStream) {
+    if !list.last().map_or(true, |t| is_punct(t, ',')) {
+        list.push(Punct::new(',', Spacing::Alone).into());
+    }
+    list.extend(added);
+}
+
+/// Adds the comma-separated elements in `added` to the start of the
+/// comma-separated list `list`.
+pub fn prepend_list(list: &mut Vec<TokenTree>, added: TokenStream) {
+    let mut added: Vec<_> = added.into_iter().collect();
+    if !(list.is_empty() || added.last().map_or(true, |t| is_punct(t, ','))) {
+        added.push(Punct::new(',', Spacing::Alone).into());
+    }
+    list.splice(0..0, added);
+}
+
+/// Returns whether `a` and `b` consist of the same tokens, ignoring spans and
+/// spacing.
+pub fn trees_eq(a: &[TokenTree], b: &[TokenTree]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| a.to_string() == b.to_string())
+}
+
+pub fn split_attrs(item: TokenStream) -> (TokenStream, TokenStream) {
+    let mut attrs = Vec::<TokenTree>::new();
+    let mut iter = item.into_iter().fuse();
+    loop {
+        use Delimiter::*;
+        use TokenTree::*;
+        match [iter.next(), iter.next()] {
+            [Some(Punct(p)), Some(Group(g))]
+                if (p.as_char(), g.delimiter()) == ('#', Bracket) =>
+            {
+                attrs.extend([p.into(), g.into()]);
+            }
+            mut trees => {
+                let trees = trees.iter_mut().flat_map(Option::take);
+                return (
+                    attrs.into_iter().collect(),
+                    trees.chain(iter).collect(),
+                );
+            }
+        };
+    }
+}
+
+/// Splits the contents of a block into its inner attributes (like
+/// `#![allow(unused)]`) and the remaining tokens.
+pub fn split_inner_attrs(body: TokenStream) -> (TokenStream, TokenStream) {
+    let mut attrs = Vec::<TokenTree>::new();
+    let mut iter = body.into_iter().fuse();
+    loop {
+        use Delimiter::*;
+        use TokenTree::*;
+        match [iter.next(), iter.next(), iter.next()] {
+            [Some(Punct(p1)), Some(Punct(p2)), Some(Group(g))]
+                if (p1.as_char(), p2.as_char(), g.delimiter())
+                    == ('#', '!', Bracket) =>
+            {
+                attrs.extend([p1.into(), p2.into(), g.into()]);
+            }
+            mut trees => {
+                let trees = trees.iter_mut().flat_map(Option::take);
+                return (
+                    attrs.into_iter().collect(),
+                    trees.chain(iter).collect(),
+                );
+            }
+        };
+    }
+}
+
+/// Splits an item (without its outer attributes) into its visibility and the
+/// remaining tokens.
+pub fn split_vis(item: TokenStream) -> (TokenStream, TokenStream) {
+    use Delimiter::*;
+    use TokenTree::*;
+    let trees: Vec<_> = item.into_iter().collect();
+    let len = match &trees[..] {
+        // In tuple struct fields, `pub` can be followed by a parenthesized
+        // type rather than a restriction like `(crate)`.
+        [Ident(i), Group(g), ..]
+            if i.to_string() == "pub"
+                && g.delimiter() == Parenthesis
+                && g.stream().into_iter().next().map_or(false, |t| {
+                    ["crate", "self", "super", "in"]
+                        .iter()
+                        .any(|k| is_ident(&t, k))
+                }) =>
+        {
+            2
+        }
+        [Ident(i), ..] if i.to_string() == "pub" => 1,
+        // `crate` is a visibility only when it doesn't start a path.
+        [Ident(i), Punct(p), ..]
+            if i.to_string() == "crate" && p.as_char() == ':' =>
+        {
+            0
+        }
+        [Ident(i), ..] if i.to_string() == "crate" => 1,
+        _ => 0,
+    };
+    let mut trees = trees.into_iter();
+    (trees.by_ref().take(len).collect(), trees.collect())
+}
+
+/// Returns a copy of `group` with its contents replaced by the result of
+/// `f`.
+pub fn map_group(
+    group: &Group,
+    f: impl FnOnce(TokenStream) -> TokenStream,
+) -> Group {
+    let mut new = Group::new(group.delimiter(), f(group.stream()));
+    new.set_span(group.span());
+    new
+}
+
+/// An item split into its outer attributes, visibility, qualifiers, and
+/// remaining tokens.
+pub struct Item {
+    pub attrs: TokenStream,
+    pub vis: TokenStream,
+    pub quals: Qualifiers,
+    pub rest: Vec<TokenTree>,
+}
+
+impl Item {
+    pub fn parse(item: TokenStream) -> Self {
+        let (attrs, rest) = split_attrs(item);
+        let (vis, rest) = split_vis(rest);
+        let mut rest: Vec<_> = rest.into_iter().collect();
+        let (quals, len) = Qualifiers::parse(&rest);
+        rest.drain(..len);
+        Self {
+            attrs,
+            vis,
+            quals,
+            rest,
+        }
+    }
+
+    /// Returns the keyword that determines the kind of this item, like `fn`
+    /// or `struct`. `auto trait` items return `trait`, and `extern` blocks
+    /// return `extern`.
+    pub fn kind(&self) -> Option<String> {
+        match &self.rest[..] {
+            [TokenTree::Ident(i), next, ..]
+                if i.to_string() == "auto" && is_ident(next, "trait") =>
+            {
+                Some("trait".into())
+            }
+            [TokenTree::Ident(i), ..] => Some(i.to_string()),
+            [TokenTree::Group(g), ..]
+                if g.delimiter() == Delimiter::Brace
+                    && self.quals.has("extern") =>
+            {
+                Some("extern".into())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the index in [`Self::rest`] of the name of this item, if it
+    /// has one.
+    pub fn name_index(&self) -> Option<usize> {
+        let kind = self.kind()?;
+        let index = match kind.as_str() {
+            "trait" if !is_ident(&self.rest[0], "trait") => 2,
+            "static"
+                if self.rest.get(1).map_or(false, |t| is_ident(t, "mut")) =>
+            {
+                2
+            }
+            "fn" | "struct" | "enum" | "union" | "trait" | "type"
+            | "const" | "static" | "mod" => 1,
+            _ => return None,
+        };
+        match self.rest.get(index) {
+            Some(TokenTree::Ident(_)) => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Returns the index in [`Self::rest`] where this item's generic
+    /// parameters start (or would start, if it has none), if it can have
+    /// them.
+    pub fn generics_index(&self) -> Option<usize> {
+        match self.kind()?.as_str() {
+            "impl" => Some(1),
+            "fn" | "struct" | "enum" | "union" | "trait" | "type" => {
+                self.name_index().map(|i| i + 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a span suitable for errors that refer to the whole item.
+    pub fn span(&self) -> Span {
+        self.rest.first().map_or_else(Span::call_site, TokenTree::span)
+    }
+
+    pub fn into_tokens(self) -> TokenStream {
+        let mut tokens = self.attrs;
+        tokens.extend(self.vis);
+        tokens.extend(self.quals.into_tokens());
+        tokens.extend(self.rest);
+        tokens
+    }
+}
@@ -19,9 +19,10 @@
 //! Attribute macros that prepend or append arbitrary syntax. Useful with
 //! [`cfg_attr`].
 //!
-//! This crate provides two attribute macros, [`prepend`] and [`append`], that
-//! add the tokens passed to them to the start or end of the item to which the
-//! attribute is applied, respectively. This is particularly useful with
+//! This crate provides attribute macros, most notably [`prepend`] and
+//! [`append`], that add the tokens passed to them to the item to which the
+//! attribute is applied. [`prepend`] and [`append`] add the tokens to the
+//! start or end of the item, respectively. This is particularly useful with
 //! [`cfg_attr`].
 //!
 //! Example
@@ -68,31 +69,53 @@
 #the-cfg_attr-attribute"]
 //! [`prepend`]: macro@prepend
 //! [`append`]: macro@append
+//!
+//! Visibility
+//! ----------
+//!
+//! [`prepend`] adds tokens before everything but the item's outer
+//! attributes, so `prepend(unsafe)` on a `pub fn` would produce `unsafe pub
+//! fn`, which is invalid. [`insert_after_vis`] instead adds the tokens after
+//! the item's visibility, if it has one:
+//!
+//! ```rust
+//! #[cfg_attr(feature = "unchecked", add_syntax::insert_after_vis(unsafe))]
+//! pub fn get(slice: &[u8], i: usize) -> u8 {
+//!     slice[i]
+//! }
+//!
+//! #[add_syntax::insert_after_vis(unsafe)]
+//! pub(crate) fn get_unchecked(slice: &[u8], i: usize) -> u8 {
+//!     *slice.get_unchecked(i)
+//! }
+//! # fn main() {
+//! #     let _ = unsafe { get_unchecked(&[1], 0) };
+//! # }
+//! ```
+//!
+//! [`insert_after_vis`]: macro@insert_after_vis
 
-use proc_macro::{Delimiter, TokenStream, TokenTree};
-
-fn split_attrs(item: TokenStream) -> (TokenStream, TokenStream) {
-    let mut attrs = Vec::<TokenTree>::new();
-    let mut iter = item.into_iter().fuse();
-    loop {
-        use Delimiter::*;
-        use TokenTree::*;
-        match [iter.next(), iter.next()] {
-            [Some(Punct(p)), Some(Group(g))]
-                if (p.as_char(), g.delimiter()) == ('#', Bracket) =>
-            {
-                attrs.extend([p.into(), g.into()]);
-            }
-            mut trees => {
-                let trees = trees.iter_mut().flat_map(Option::take);
-                return (
-                    attrs.into_iter().collect(),
-                    trees.chain(iter).collect(),
-                );
-            }
-        };
-    }
-}
+use proc_macro::TokenStream;
+
+mod attrs;
+mod body;
+mod each;
+mod error;
+mod fields;
+mod generics;
+mod head;
+mod item;
+mod op;
+mod paths;
+mod qualifiers;
+mod replace;
+mod select;
+mod signature;
+mod template;
+mod version;
+
+use error::Error;
+use item::{split_attrs, split_vis};
 
 /// Adds the tokens provided to this attribute to the start of the item to
 /// which this attribute is applied.
@@ -103,6 +126,47 @@ pub fn prepend(attr: TokenStream, item: TokenStream) -> TokenStream {
     item_attrs
 }
 
+/// Adds the tokens provided to this attribute to the item to which this
+/// attribute is applied, after the item's outer attributes and visibility.
+///
+/// This is like [`prepend`], but `pub`, `pub(...)`, and `crate` remain at the
+/// start of the item, so tokens like `unsafe`, `const`, and `async` can be
+/// added to public items.
+///
+/// [`prepend`]: macro@prepend
+#[proc_macro_attribute]
+pub fn insert_after_vis(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let (mut item_attrs, rest) = split_attrs(item);
+    let (vis, rest) = split_vis(rest);
+    item_attrs.extend(vis.into_iter().chain(attr).chain(rest));
+    item_attrs
+}
+
+/// Adds the qualifiers provided to this attribute to the item to which this
+/// attribute is applied.
+///
+/// The qualifiers `default`, `const`, `async`, `unsafe`, and `extern` (with
+/// an optional ABI) are accepted, separated by commas. They are merged with
+/// the item's existing qualifiers and placed after its visibility in the
+/// order required by the language, regardless of the order in which they
+/// were added:
+///
+/// ```rust
+/// #[add_syntax::qualify(const)]
+/// #[add_syntax::qualify(unsafe, extern "C")]
+/// pub fn zero() -> u8 {
+///     0
+/// }
+///
+/// // The function is now `pub const unsafe extern "C" fn zero() -> u8`.
+/// const ZERO: u8 = unsafe { zero() };
+/// # fn main() {}
+/// ```
+#[proc_macro_attribute]
+pub fn qualify(attr: TokenStream, item: TokenStream) -> TokenStream {
+    qualifiers::qualify(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
 /// Adds the tokens provided to this attribute to the end of the item to
 /// which this attribute is applied.
 #[proc_macro_attribute]
@@ -110,3 +174,1390 @@ pub fn append(attr: TokenStream, mut item: TokenStream) -> TokenStream {
     item.extend(attr);
     item
 }
+
+/// Adds the tokens provided to this attribute to the start of the body of the
+/// `mod`, `impl`, `trait`, or `extern` block to which this attribute is
+/// applied.
+///
+/// The tokens are added after any inner attributes (like `#![allow(...)]`) at
+/// the start of the body:
+///
+/// ```rust
+/// #[add_syntax::prepend_inner(
+///     pub const VERSION: u32 = 2;
+/// )]
+/// mod config {
+///     #![allow(dead_code)]
+///
+///     fn unused() {}
+/// }
+///
+/// # fn main() {
+/// assert_eq!(config::VERSION, 2);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn prepend_inner(attr: TokenStream, item: TokenStream) -> TokenStream {
+    body::prepend_inner(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the tokens provided to this attribute to the end of the body of the
+/// `mod`, `impl`, `trait`, or `extern` block to which this attribute is
+/// applied.
+///
+/// This can be used to conditionally add associated items:
+///
+/// ```rust
+/// pub struct Name(&'static str);
+///
+/// #[cfg_attr(feature = "std", add_syntax::append_inner(
+///     pub fn to_string(&self) -> String {
+///         self.0.into()
+///     }
+/// ))]
+/// impl Name {
+///     pub fn as_str(&self) -> &str {
+///         self.0
+///     }
+/// }
+/// ```
+///
+/// Inner attributes at the start of the body are unaffected:
+///
+/// ```rust
+/// #[add_syntax::append_inner(
+///     pub fn answer() -> u32 {
+///         42
+///     }
+/// )]
+/// mod math {
+///     #![allow(dead_code)]
+///
+///     fn unused() {}
+/// }
+///
+/// # fn main() {
+/// assert_eq!(math::answer(), 42);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn append_inner(attr: TokenStream, item: TokenStream) -> TokenStream {
+    body::append_inner(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the statements provided to this attribute to the start of the body of
+/// the function to which this attribute is applied.
+///
+/// The statements are added after any inner attributes at the start of the
+/// body. A trailing semicolon is added to the statements if needed.
+///
+/// ```rust
+/// #[cfg_attr(debug_assertions, add_syntax::prepend_stmts(
+///     assert!(denominator != 0, "division by zero");
+/// ))]
+/// pub fn divide(numerator: u32, denominator: u32) -> u32 {
+///     numerator / denominator
+/// }
+/// ```
+///
+/// ```rust
+/// #[add_syntax::prepend_stmts(log.push("start"))]
+/// fn run(log: &mut Vec<&str>) -> usize {
+///     #![allow(clippy::ptr_arg)]
+///     log.push("body");
+///     log.len()
+/// }
+///
+/// # fn main() {
+/// let mut log = Vec::new();
+/// assert_eq!(run(&mut log), 2);
+/// assert_eq!(log, ["start", "body"]);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn prepend_stmts(attr: TokenStream, item: TokenStream) -> TokenStream {
+    body::prepend_stmts(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the statements provided to this attribute to the end of the body of
+/// the function to which this attribute is applied, before its tail
+/// expression.
+///
+/// If the function has no return type, the statements are added after all
+/// existing statements. A trailing semicolon is added to the statements if
+/// needed.
+///
+/// ```rust
+/// macro_rules! log {
+///     ($($tokens:tt)*) => { $($tokens)* };
+/// }
+///
+/// #[add_syntax::append_stmts(log.push("end"))]
+/// fn sign(log: &mut Vec<&str>, x: i32) -> i32 {
+///     log.push("start");
+///     if x < 0 { -1 } else { 1 }
+/// }
+///
+/// #[add_syntax::append_stmts(log.push("end");)]
+/// fn name(log: &mut Vec<&str>, x: u8) -> usize {
+///     log.push("start");
+///     match x {
+///         0 => log.len(),
+///         _ => 0,
+///     }
+/// }
+///
+/// #[add_syntax::append_stmts(log.push("end");)]
+/// fn parse(log: &mut Vec<&str>, s: &str) -> usize {
+///     let _ = match s.parse::<u32>() {
+///         Ok(value) => value,
+///         Err(_) => return 0,
+///     };
+///     'outer: loop {
+///         break 'outer;
+///     }
+///     log! {
+///         log.push("macro");
+///     }
+///     log.len()
+/// }
+///
+/// #[add_syntax::append_stmts(log.push("end");)]
+/// fn count(log: &mut Vec<&str>) -> usize {
+///     log! { log }.len()
+/// }
+///
+/// #[add_syntax::append_stmts(log.push("end");)]
+/// fn run(log: &mut Vec<&str>) {
+///     log.push("start")
+/// }
+///
+/// # fn main() {
+/// let mut log = Vec::new();
+/// assert_eq!(sign(&mut log, -5), -1);
+/// assert_eq!(log, ["start", "end"]);
+///
+/// let mut log = Vec::new();
+/// assert_eq!(name(&mut log, 0), 2);
+///
+/// let mut log = Vec::new();
+/// assert_eq!(parse(&mut log, "1"), 2);
+/// assert_eq!(log, ["macro", "end"]);
+///
+/// let mut log = Vec::new();
+/// assert_eq!(count(&mut log), 1);
+///
+/// let mut log = Vec::new();
+/// run(&mut log);
+/// assert_eq!(log, ["start", "end"]);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn append_stmts(attr: TokenStream, item: TokenStream) -> TokenStream {
+    body::append_stmts(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Wraps the body of the function to which this attribute is applied in an
+/// `unsafe` block.
+///
+/// Inner attributes at the start of the body remain outside the block. This
+/// is useful with the `unsafe_op_in_unsafe_fn` lint when a function is only
+/// conditionally `unsafe`:
+///
+/// ```rust
+/// #![deny(unsafe_op_in_unsafe_fn)]
+///
+/// #[cfg_attr(feature = "unchecked", add_syntax::qualify(unsafe))]
+/// #[cfg_attr(feature = "unchecked", add_syntax::unsafe_body)]
+/// pub fn get(slice: &[u8], i: usize) -> u8 {
+///     #[cfg(feature = "unchecked")]
+///     return *slice.get_unchecked(i);
+///     #[cfg(not(feature = "unchecked"))]
+///     return slice[i];
+/// }
+/// ```
+///
+/// Inner attributes like `#![inline]` still apply to the function:
+///
+/// ```rust
+/// #![deny(unsafe_op_in_unsafe_fn)]
+///
+/// /// # Safety
+/// ///
+/// /// `slice` must not be empty.
+/// #[add_syntax::unsafe_body]
+/// pub unsafe fn first(slice: &[u8]) -> u8 {
+///     #![inline]
+///     *slice.get_unchecked(0)
+/// }
+///
+/// # fn main() {
+/// assert_eq!(unsafe { first(&[7, 8]) }, 7);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn unsafe_body(attr: TokenStream, item: TokenStream) -> TokenStream {
+    body::unsafe_body(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Replaces the body of the function to which this attribute is applied with
+/// the block provided to this attribute.
+///
+/// The function's signature and attributes are unchanged. The block may be
+/// given with or without its braces. Functions without a body (like those in
+/// trait definitions) are given one.
+///
+/// ```rust
+/// #[cfg_attr(target_arch = "wasm32", add_syntax::replace_body({
+///     Err(std::io::ErrorKind::Unsupported.into())
+/// }))]
+/// pub fn hostname() -> std::io::Result<String> {
+///     std::fs::read_to_string("/etc/hostname")
+/// }
+/// ```
+///
+/// Provided methods can be added to trait definitions:
+///
+/// ```rust
+/// pub trait Shape {
+///     fn sides(&self) -> u8;
+///
+///     #[add_syntax::replace_body(self.sides() * x)]
+///     fn scaled(&self, x: u8) -> u8;
+/// }
+///
+/// struct Square;
+///
+/// impl Shape for Square {
+///     fn sides(&self) -> u8 {
+///         4
+///     }
+/// }
+///
+/// #[add_syntax::replace_body({
+///     a + b
+/// })]
+/// fn add(a: u8, b: u8) -> u8 {
+///     a - b
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Square.scaled(3), 12);
+/// assert_eq!(add(2, 3), 5);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn replace_body(attr: TokenStream, item: TokenStream) -> TokenStream {
+    body::replace_body(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Replaces the body of the function to which this attribute is applied with
+/// [`unimplemented!()`](unimplemented).
+///
+/// This is a shorthand for `replace_body(unimplemented!())` that also allows
+/// the function's parameters to be unused.
+///
+/// ```rust
+/// #![deny(unused_variables)]
+///
+/// pub trait Reader {
+///     #[add_syntax::stub]
+///     fn read(&self, buf: &mut [u8]) -> usize;
+/// }
+///
+/// struct Empty;
+///
+/// impl Reader for Empty {}
+///
+/// #[add_syntax::stub]
+/// fn compress(data: &[u8]) -> Vec<u8> {
+///     data.to_vec()
+/// }
+///
+/// # fn main() {
+/// assert!(std::panic::catch_unwind(|| Empty.read(&mut [])).is_err());
+/// assert!(std::panic::catch_unwind(|| compress(&[1, 2])).is_err());
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn stub(attr: TokenStream, item: TokenStream) -> TokenStream {
+    body::stub(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Removes the tokens provided to this attribute from the start of the item
+/// to which this attribute is applied.
+///
+/// The tokens must appear in the item's visibility, qualifiers, or keywords,
+/// like `unsafe`, `const`, or the `mut` in `static mut`. `remove(pub)`
+/// removes any visibility, including `pub(crate)` and `pub(in path)`.
+///
+/// This allows the more restrictive form of an item to be the default:
+///
+/// ```rust
+/// #[cfg_attr(feature = "safe-api", add_syntax::remove(unsafe))]
+/// pub unsafe fn reset() {}
+/// ```
+///
+/// ```rust
+/// #[add_syntax::remove(mut)]
+/// static mut LIMIT: u32 = 10;
+///
+/// mod a {
+///     #[add_syntax::remove(pub)]
+///     pub(crate) fn value() -> u8 {
+///         1
+///     }
+/// }
+///
+/// mod b {
+///     pub fn value() -> u8 {
+///         2
+///     }
+/// }
+///
+/// use a::*;
+/// use b::*;
+///
+/// # fn main() {
+/// // `LIMIT` can be read without `unsafe`.
+/// assert_eq!(LIMIT, 10);
+/// // `a::value` is private, so `value` refers only to `b::value`.
+/// assert_eq!(value(), 2);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn remove(attr: TokenStream, item: TokenStream) -> TokenStream {
+    head::remove(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Replaces every occurrence of a sequence of tokens in the item to which this
+/// attribute is applied.
+///
+/// This attribute accepts two bracketed lists of tokens:
+/// `replace(from = [...], to = [...])`. Occurrences are found anywhere in the
+/// item, including inside parentheses, brackets, and braces. In `from`, `_`
+/// matches any single token or delimited group; each `_` in `to` is replaced
+/// by the tokens matched by the corresponding `_` in `from`.
+///
+/// ```rust
+/// use std::cell::RefCell;
+/// use std::sync::Mutex;
+///
+/// #[cfg_attr(feature = "sync", add_syntax::replace(
+///     from = [RefCell<_>],
+///     to = [Mutex<_>],
+/// ))]
+/// pub struct Counter {
+///     count: RefCell<u64>,
+/// }
+/// ```
+///
+/// Because `_` matches a single token, only the inner `RefCell<u8>` in
+/// `RefCell<RefCell<u8>>` matches `RefCell<_>`:
+///
+/// ```rust
+/// use std::cell::{Cell, RefCell};
+///
+/// #[add_syntax::replace(from = [RefCell<_>], to = [Cell<_>])]
+/// pub struct Flags {
+///     nested: RefCell<RefCell<u8>>,
+///     list: Vec<RefCell<bool>>,
+/// }
+///
+/// #[add_syntax::replace(from = [_.borrow_mut()], to = [_.get_mut()])]
+/// fn reset(cell: &mut RefCell<u8>) {
+///     *cell.borrow_mut() = 0;
+/// }
+///
+/// # fn main() {
+/// let flags = Flags {
+///     nested: RefCell::new(Cell::new(1)),
+///     list: vec![Cell::new(true)],
+/// };
+/// assert_eq!(flags.nested.borrow().get(), 1);
+/// assert!(flags.list[0].get());
+///
+/// let mut cell = RefCell::new(5);
+/// reset(&mut cell);
+/// assert_eq!(cell.into_inner(), 0);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn replace(attr: TokenStream, item: TokenStream) -> TokenStream {
+    replace::replace(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the generic parameters provided to this attribute to the item to which
+/// this attribute is applied.
+///
+/// The parameters may be given with or without their enclosing angle
+/// brackets. They are added after the item's existing parameters, except that
+/// lifetimes are placed before all type and const parameters. If the item has
+/// no generic parameters, they are added after its name (or after `impl`).
+///
+/// ```rust
+/// #[add_syntax::add_generics(<'a>)]
+/// pub struct Parser<T> {
+///     input: &'a str,
+///     state: T,
+/// }
+///
+/// // The struct is now `Parser<'a, T>`.
+/// # fn main() {
+/// let parser: Parser<'static, u8> = Parser { input: "", state: 0 };
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn add_generics(attr: TokenStream, item: TokenStream) -> TokenStream {
+    generics::add_generics(attr, item)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the `where` predicates provided to this attribute to the item to which
+/// this attribute is applied.
+///
+/// The predicates are added to the end of the item's `where` clause, which is
+/// created if it doesn't exist.
+///
+/// ```rust
+/// # use std::fmt::Debug;
+/// #[cfg_attr(feature = "debug", add_syntax::add_where(T: Debug))]
+/// pub struct Wrapper<T>(T);
+///
+/// #[add_syntax::add_where(T: Debug)]
+/// pub fn print<T>(value: T)
+/// where
+///     T: Clone,
+/// {
+///     println!("{:?}", value.clone());
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn add_where(attr: TokenStream, item: TokenStream) -> TokenStream {
+    generics::add_where(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the bounds provided to this attribute to the supertraits of the trait
+/// to which this attribute is applied.
+///
+/// ```rust
+/// #[cfg_attr(
+///     feature = "threadsafe",
+///     add_syntax::add_supertraits(Send + Sync),
+/// )]
+/// pub trait Handler {
+///     fn handle(&self);
+/// }
+///
+/// #[add_syntax::add_supertraits(Send)]
+/// pub trait Service: Clone {}
+///
+/// fn assert_send<T: Service>(service: T) -> impl Send + Clone {
+///     service
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn add_supertraits(attr: TokenStream, item: TokenStream) -> TokenStream {
+    generics::add_supertraits(attr, item)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the parameters provided to this attribute to the start of the
+/// parameter list of the function to which this attribute is applied.
+///
+/// The parameters are added after the `self` parameter, if there is one:
+///
+/// ```rust
+/// pub struct Counter(u32);
+///
+/// impl Counter {
+///     #[add_syntax::prepend_params(step: u32)]
+///     pub fn advance(&mut self) -> u32 {
+///         self.0 += step;
+///         self.0
+///     }
+///
+///     #[add_syntax::prepend_params(label: &str,)]
+///     pub fn describe(&self, width: usize,) -> String {
+///         format!("{}: {:>width$}", label, self.0, width = width)
+///     }
+///
+///     #[add_syntax::prepend_params(extra: u32)]
+///     pub fn into_total(self: Box<Self>,) -> u32 {
+///         self.0 + extra
+///     }
+/// }
+///
+/// #[add_syntax::prepend_params(s: &str)]
+/// pub fn repeat(n: usize) -> String {
+///     s.repeat(n)
+/// }
+///
+/// #[add_syntax::prepend_params(value: u8)]
+/// pub fn identity() -> u8 {
+///     value
+/// }
+///
+/// # fn main() {
+/// let mut counter = Counter(0);
+/// assert_eq!(counter.advance(2), 2);
+/// assert_eq!(counter.describe("count", 3), "count:   2");
+/// assert_eq!(Box::new(counter).into_total(5), 7);
+/// assert_eq!(repeat("ab", 2), "abab");
+/// assert_eq!(identity(4), 4);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn prepend_params(attr: TokenStream, item: TokenStream) -> TokenStream {
+    signature::prepend_params(attr, item)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the parameters provided to this attribute to the end of the parameter
+/// list of the function to which this attribute is applied.
+///
+/// ```rust
+/// # pub struct Allocator;
+/// #[cfg_attr(feature = "allocator", add_syntax::append_params(
+///     alloc: &Allocator,
+/// ))]
+/// pub fn make_buffer(len: usize) -> Vec<u8> {
+///     vec![0; len]
+/// }
+/// ```
+///
+/// ```rust
+/// pub struct Scale(u32);
+///
+/// impl Scale {
+///     #[add_syntax::append_params(value: u32)]
+///     pub fn apply(&self) -> u32 {
+///         self.0 * value
+///     }
+/// }
+///
+/// #[add_syntax::append_params(suffix: &str,)]
+/// pub fn greet(name: &str,) -> String {
+///     format!("Hello, {}{}", name, suffix)
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Scale(3).apply(4), 12);
+/// assert_eq!(greet("world", "!"), "Hello, world!");
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn append_params(attr: TokenStream, item: TokenStream) -> TokenStream {
+    signature::append_params(attr, item)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+/// Sets the return type of the function to which this attribute is applied to
+/// the type provided to this attribute.
+///
+/// `_` in the provided type is replaced by the function's existing return
+/// type (or `()` if it has none), so the return type can be wrapped in
+/// another type. If no type is provided, the return type is removed.
+///
+/// ```rust
+/// #[add_syntax::set_return(Option<_>)]
+/// pub fn first(s: &str) -> char {
+///     s.chars().next()
+/// }
+///
+/// #[add_syntax::set_return(Result<_, String>)]
+/// pub fn check(s: &str) {
+///     if s.is_empty() {
+///         return Err("empty".into());
+///     }
+///     Ok(())
+/// }
+///
+/// #[add_syntax::set_return()]
+/// pub fn log(s: &str) -> u32 {
+///     println!("{}", s);
+/// }
+///
+/// # fn main() {
+/// assert_eq!(first("abc"), Some('a'));
+/// assert_eq!(check(""), Err("empty".into()));
+/// let () = log("message");
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn set_return(attr: TokenStream, item: TokenStream) -> TokenStream {
+    signature::set_return(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the fields provided to this attribute to the start of the struct or
+/// union to which this attribute is applied.
+///
+/// This works with both named and tuple fields. Separating commas are added
+/// as needed.
+///
+/// ```rust
+/// #[add_syntax::prepend_fields(pub id: u32)]
+/// pub struct User {
+///     pub name: &'static str,
+/// }
+///
+/// #[add_syntax::prepend_fields(pub u8,)]
+/// pub struct Tagged<T>(pub T,)
+/// where
+///     T: Copy;
+///
+/// #[add_syntax::prepend_fields(pub bool)]
+/// pub struct Flag();
+///
+/// # fn main() {
+/// let user = User { id: 1, name: "a" };
+/// let tagged = Tagged(1_u8, 'x');
+/// let flag = Flag(true);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn prepend_fields(attr: TokenStream, item: TokenStream) -> TokenStream {
+    fields::prepend_fields(attr, item)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the fields provided to this attribute to the end of the struct or
+/// union to which this attribute is applied.
+///
+/// This works with both named and tuple fields. Separating commas are added
+/// as needed.
+///
+/// ```rust
+/// #[add_syntax::append_fields(
+///     pub hits: u64,
+///     pub misses: u64,
+/// )]
+/// pub struct Stats {
+///     pub lookups: u64
+/// }
+///
+/// # fn main() {
+/// let stats = Stats { lookups: 2, hits: 1, misses: 1 };
+/// # }
+/// ```
+///
+/// ```rust
+/// #[add_syntax::append_fields(pub char)]
+/// pub struct Tagged<T>(pub T,)
+/// where
+///     T: Copy;
+///
+/// #[add_syntax::append_fields(pub b: u8,)]
+/// pub struct Pair {
+///     pub a: u16,
+/// }
+///
+/// # fn main() {
+/// let tagged = Tagged(1_u8, 'x');
+/// let pair = Pair { a: 1, b: 2 };
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn append_fields(attr: TokenStream, item: TokenStream) -> TokenStream {
+    fields::append_fields(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the variants provided to this attribute to the start of the enum to
+/// which this attribute is applied.
+///
+/// Separating commas are added as needed.
+///
+/// ```rust
+/// #[add_syntax::prepend_variants(Zero)]
+/// pub enum Small {
+///     One,
+///     Two
+/// }
+///
+/// #[add_syntax::prepend_variants(Empty,)]
+/// pub enum Shape {
+///     Circle(f32),
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Small::Zero as u8, 0);
+/// assert_eq!(Small::Two as u8, 2);
+/// let shape = Shape::Empty;
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn prepend_variants(attr: TokenStream, item: TokenStream) -> TokenStream {
+    fields::prepend_variants(attr, item)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the variants provided to this attribute to the end of the enum to
+/// which this attribute is applied.
+///
+/// Separating commas are added as needed.
+///
+/// ```rust
+/// #[cfg_attr(feature = "std", add_syntax::append_variants(
+///     Io(std::io::Error),
+/// ))]
+/// pub enum Error {
+///     InvalidInput,
+///     Overflow,
+/// }
+/// ```
+///
+/// ```rust
+/// #[add_syntax::append_variants(Three)]
+/// pub enum Small {
+///     One = 1,
+///     Two,
+/// }
+///
+/// #[add_syntax::append_variants(Square(f32),)]
+/// pub enum Shape {
+///     Circle(f32)
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Small::Three as u8, 3);
+/// let shape = Shape::Square(1.0);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn append_variants(attr: TokenStream, item: TokenStream) -> TokenStream {
+    fields::append_variants(attr, item)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+/// Replaces the visibility of the item to which this attribute is applied
+/// with the visibility provided to this attribute.
+///
+/// Unlike `prepend(pub)`, this works with items that already have a
+/// visibility. If no visibility is provided, the item becomes private.
+///
+/// ```rust
+/// #[cfg_attr(feature = "unstable-internals", add_syntax::set_vis(pub))]
+/// pub(crate) fn parse_header(bytes: &[u8]) -> usize {
+///     bytes.len()
+/// }
+/// ```
+///
+/// ```rust
+/// mod a {
+///     #[add_syntax::set_vis(pub(crate))]
+///     fn double(x: u8) -> u8 {
+///         x * 2
+///     }
+///
+///     #[add_syntax::set_vis()]
+///     pub fn value() -> u8 {
+///         1
+///     }
+/// }
+///
+/// mod b {
+///     pub fn value() -> u8 {
+///         2
+///     }
+/// }
+///
+/// use a::*;
+/// use b::*;
+///
+/// # fn main() {
+/// assert_eq!(a::double(2), 4);
+/// // `a::value` is private, so `value` refers only to `b::value`.
+/// assert_eq!(value(), 2);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn set_vis(attr: TokenStream, item: TokenStream) -> TokenStream {
+    head::set_vis(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Sets the visibility of the fields of the struct or union to which this
+/// attribute is applied to the visibility provided to this attribute.
+///
+/// By default, the visibility of every field is set. To set the visibility of
+/// specific fields only, list their names (or indices, for tuple structs)
+/// after the visibility, separated by commas. If no visibility is provided,
+/// the fields become private.
+///
+/// ```rust
+/// #[cfg_attr(feature = "bench", add_syntax::fields_vis(pub))]
+/// pub struct Cache {
+///     entries: Vec<u64>,
+///     capacity: usize,
+/// }
+///
+/// mod id {
+///     pub struct Names(pub &'static str, pub &'static str);
+///
+///     #[add_syntax::fields_vis(pub, 0)]
+///     pub struct Id(u32, u32);
+///
+///     impl Id {
+///         pub fn new() -> Self {
+///             Id(1, 2)
+///         }
+///     }
+///
+///     impl std::ops::Deref for Id {
+///         type Target = Names;
+///
+///         fn deref(&self) -> &Names {
+///             &Names("first", "second")
+///         }
+///     }
+/// }
+///
+/// # fn main() {
+/// let id = id::Id::new();
+/// assert_eq!(id.0, 1);
+/// // `Id::1` is still private, so this refers to `Names::1`.
+/// assert_eq!(id.1, "second");
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn fields_vis(attr: TokenStream, item: TokenStream) -> TokenStream {
+    fields::fields_vis(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Renames the item to which this attribute is applied to the identifier
+/// provided to this attribute.
+///
+/// This works with functions, structs, enums, unions, traits, type aliases,
+/// constants, statics, and modules.
+///
+/// ```rust
+/// #[cfg_attr(feature = "compat", add_syntax::rename(OldName))]
+/// pub struct NewName;
+///
+/// #[add_syntax::rename(answer)]
+/// pub const fn question() -> u8 {
+///     42
+/// }
+///
+/// # fn main() {
+/// assert_eq!(answer(), 42);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn rename(attr: TokenStream, item: TokenStream) -> TokenStream {
+    head::rename(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Replaces identifiers throughout the item to which this attribute is
+/// applied.
+///
+/// This attribute accepts a comma-separated list of replacements in the form
+/// `Old => New`. Every identifier in the item that matches `Old`, including
+/// those inside parentheses, brackets, and braces, is replaced with `New`.
+/// Lifetimes and string literals are left unchanged.
+///
+/// ```rust
+/// use std::rc::Rc;
+/// use std::sync::Arc;
+///
+/// #[cfg_attr(feature = "sync", add_syntax::replace_ident(Rc => Arc))]
+/// pub struct Shared<T>(Rc<T>);
+///
+/// #[cfg_attr(feature = "sync", add_syntax::replace_ident(Rc => Arc))]
+/// impl<T> Shared<T> {
+///     pub fn new(value: T) -> Self {
+///         Self(Rc::new(value))
+///     }
+/// }
+/// ```
+///
+/// ```rust
+/// pub struct Parser<'x> {
+///     text: &'x str,
+/// }
+///
+/// impl<'x> Parser<'x> {
+///     #[add_syntax::replace_ident(x => text)]
+///     pub fn label(&self) -> (&'x str, &'static str) {
+///         (self.x, "x")
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Parser { text: "abc" }.label(), ("abc", "x"));
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn replace_ident(attr: TokenStream, item: TokenStream) -> TokenStream {
+    replace::replace_ident(attr, item)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+/// Replaces path prefixes throughout the item to which this attribute is
+/// applied.
+///
+/// This attribute accepts a comma-separated list of replacements in the form
+/// `old::prefix => new::prefix`. Paths in `use` declarations (including
+/// nested ones like `use std::{fmt, vec::Vec}`), expressions, types, and
+/// macro invocations are matched by segment, so `std => core` replaces the
+/// `std` in `::std::mem` but not the one in `my_std::mem`. When multiple
+/// prefixes match, the longest one is used.
+///
+/// This is useful for crates that support `no_std`:
+///
+/// ```rust
+/// extern crate alloc;
+///
+/// #[cfg_attr(not(feature = "std"), add_syntax::remap_paths(
+///     std => core,
+///     std::vec => alloc::vec,
+/// ))]
+/// mod imports {
+///     pub use std::{fmt, mem, vec::Vec};
+///
+///     pub fn make() -> Vec<u8> {
+///         std::vec![0; mem::size_of::<fmt::Error>()]
+///     }
+/// }
+/// ```
+///
+/// Paths are remapped before name resolution, so the rewritten paths are
+/// the only ones that need to exist:
+///
+/// ```rust
+/// use std::collections::VecDeque;
+///
+/// #[add_syntax::remap_paths(std::vec::Vec => std::collections::VecDeque)]
+/// mod queue {
+///     // This imports `VecDeque` once remapped.
+///     use std::{fmt::Write, vec::Vec};
+///
+///     pub fn make() -> (std::vec::Vec<u8>, String) {
+///         let mut items = VecDeque::new();
+///         items.push_back(2);
+///         items.push_front(1);
+///         let mut text = String::new();
+///         write!(text, "{:?}", items).unwrap();
+///         (items, text)
+///     }
+/// }
+///
+/// # fn main() {
+/// let (items, text): (VecDeque<u8>, _) = queue::make();
+/// assert_eq!(items, [1, 2]);
+/// assert_eq!(text, "[1, 2]");
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn remap_paths(attr: TokenStream, item: TokenStream) -> TokenStream {
+    paths::remap_paths(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Replaces the item to which this attribute is applied with the tokens
+/// provided to this attribute, in which `$item` is replaced by the item.
+///
+/// This allows an item to be placed inside another item or a macro
+/// invocation. `$attrs` is replaced by the item's outer attributes; if it is
+/// used, `$item` doesn't include them. The variables and `[<...>]`
+/// identifiers supported by [`append_impl`] are also available.
+///
+/// ```rust
+/// #[cfg_attr(feature = "hidden", add_syntax::wrap(
+///     const _: () = {
+///         $item
+///     };
+/// ))]
+/// impl Default for Config {
+///     fn default() -> Self {
+///         Config
+///     }
+/// }
+/// # struct Config;
+///
+/// #[add_syntax::wrap(mod inner { $attrs pub $item })]
+/// #[derive(Debug, Default)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// # fn main() {
+/// println!("{:?}", inner::Point::default());
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn wrap(attr: TokenStream, item: TokenStream) -> TokenStream {
+    template::wrap(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Adds the tokens provided to this attribute after the item to which this
+/// attribute is applied, replacing variables that refer to the item.
+///
+/// The following variables are supported:
+///
+/// * `$name`: the item's name.
+/// * `$generics`: the item's generic parameters, without defaults.
+/// * `$generic_args`: the item's generic parameters as arguments, like `'a, T,
+///   N` for `<'a, T: 'a, const N: usize>`.
+/// * `$where`: the item's `where` clause, if any.
+///
+/// This is useful for generating trait implementations:
+///
+/// ```rust
+/// #[cfg_attr(feature = "send", add_syntax::append_impl(
+///     unsafe impl<$generics> Send for $name<$generic_args> $where {}
+/// ))]
+/// pub struct Handle<'a, T: 'a = ()>
+/// where
+///     T: Clone,
+/// {
+///     ptr: *const &'a T,
+/// }
+/// ```
+///
+/// As with the `paste` crate, `[<...>]` is replaced by an identifier formed
+/// by concatenating its contents, which may include variables, identifiers,
+/// and literals. Each part may be followed by `:snake`, `:camel`, `:upper`,
+/// or `:lower` to change its case:
+///
+/// ```rust
+/// #[add_syntax::append_impl(
+///     #[derive(Default)]
+///     pub struct [<$name Builder>] {
+///         name: String,
+///     }
+///
+///     pub fn [<new_ $name:snake _builder>]() -> [<$name Builder>] {
+///         Default::default()
+///     }
+/// )]
+/// pub struct ServerConfig {
+///     name: String,
+/// }
+///
+/// # fn main() {
+/// let _: ServerConfigBuilder = new_server_config_builder();
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn append_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    template::append_impl(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Applies the operation provided to this attribute to each item in the
+/// `mod`, `impl`, `trait`, or `extern` block to which this attribute is
+/// applied.
+///
+/// The operation is the name of one of the attributes in this crate,
+/// followed by its arguments in parentheses. It can be preceded by filters:
+/// item kinds like `fn` and `const`, and `name = "..."`, where `*` matches
+/// any sequence of characters and `?` matches any single character. If
+/// filters are given, the operation is applied only to items that match at
+/// least one of the kinds and at least one of the names.
+///
+/// ```rust
+/// pub struct Buffer([u8; 4]);
+///
+/// #[add_syntax::each(fn, name = "get_*", qualify(const))]
+/// impl Buffer {
+///     pub const EMPTY: Buffer = {
+///         let bytes = [0; 4];
+///         Buffer(bytes)
+///     };
+///
+///     pub const SMALL: bool = Self::LEN < 8;
+///     const LEN: usize = 4;
+///
+///     pub fn get_first(&self) -> u8 {
+///         self.0[0]
+///     }
+///
+///     pub fn callback() -> impl Fn() {
+///         || {}
+///     }
+///
+///     pub fn get_last(&self) -> u8 {
+///         self.0[3]
+///     }
+///
+///     // This function can't be `const`.
+///     pub fn set_first(&mut self, value: u8) {
+///         println!("setting the first byte to {}", value);
+///         self.0[0] = value;
+///     }
+/// }
+///
+/// const FIRST: u8 = Buffer::EMPTY.get_first();
+/// const LAST: u8 = Buffer([1, 2, 3, 4]).get_last();
+///
+/// # fn main() {
+/// assert_eq!((FIRST, LAST), (0, 4));
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn each(attr: TokenStream, item: TokenStream) -> TokenStream {
+    each::each(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Removes the attributes with the paths provided to this attribute from
+/// the item to which this attribute is applied.
+///
+/// Paths are separated by commas and compared exactly, regardless of the
+/// attribute's arguments, so `inline` removes both `#[inline]` and
+/// `#[inline(always)]`. Only the item's outer attributes are removed; see
+/// [`strip_attrs_recursive`] to remove attributes from nested items and
+/// fields too.
+///
+/// This is useful for disabling attributes that aren't supported by some
+/// compilers:
+///
+/// ```rust
+/// #[cfg_attr(feature = "no_track_caller", add_syntax::strip_attrs(
+///     track_caller,
+/// ))]
+/// #[inline]
+/// #[track_caller]
+/// pub fn check(value: bool) {
+///     assert!(value);
+/// }
+/// ```
+///
+/// ```rust
+/// #![deny(unused_doc_comments)]
+///
+/// // Without `strip_attrs`, the doc comment would trigger the lint above.
+/// #[add_syntax::strip_attrs(doc)]
+/// /// Functions from the C standard library.
+/// extern "C" {
+///     fn abs(x: i32) -> i32;
+/// }
+///
+/// #[add_syntax::strip_attrs(track_caller)]
+/// #[track_caller]
+/// fn line() -> u32 {
+///     std::panic::Location::caller().line()
+/// }
+///
+/// # fn main() {
+/// assert_eq!(unsafe { abs(-3) }, 3);
+/// // `line` reports its own location rather than its caller's.
+/// assert_ne!(line(), line!());
+/// # }
+/// ```
+///
+/// [`strip_attrs_recursive`]: macro@strip_attrs_recursive
+#[proc_macro_attribute]
+pub fn strip_attrs(attr: TokenStream, item: TokenStream) -> TokenStream {
+    attrs::strip_attrs(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Removes the attributes with the paths provided to this attribute from
+/// the item to which this attribute is applied and everything inside it.
+///
+/// This is like [`strip_attrs`], but attributes are also removed from nested
+/// items, fields, statements, and expressions, and inner attributes (like
+/// `#![allow(...)]`) are removed too.
+///
+/// ```rust
+/// #[add_syntax::strip_attrs_recursive(inline, must_use)]
+/// impl Point {
+///     #[inline]
+///     pub fn x(&self) -> i32 {
+///         self.x
+///     }
+///
+///     #[must_use]
+///     #[inline(always)]
+///     pub fn y(&self) -> i32 {
+///         self.y
+///     }
+/// }
+/// # struct Point {
+/// #     x: i32,
+/// #     y: i32,
+/// # }
+/// ```
+///
+/// ```rust
+/// pub struct Counter(u32);
+///
+/// #[add_syntax::strip_attrs_recursive(track_caller, deny)]
+/// impl Counter {
+///     #[track_caller]
+///     pub fn line(&self) -> u32 {
+///         #![deny(unused_variables)]
+///         let unused = self.0;
+///         std::panic::Location::caller().line()
+///     }
+/// }
+///
+/// # fn main() {
+/// // `line` reports its own location rather than its caller's.
+/// assert_ne!(Counter(0).line(), line!());
+/// # }
+/// ```
+///
+/// [`strip_attrs`]: macro@strip_attrs
+#[proc_macro_attribute]
+pub fn strip_attrs_recursive(
+    attr: TokenStream,
+    item: TokenStream,
+) -> TokenStream {
+    attrs::strip_attrs_recursive(attr, item)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+/// Applies the operation in the first arm provided to this attribute whose
+/// predicate is true to the item to which this attribute is applied.
+///
+/// Each arm consists of a [`cfg`] predicate, `=>`, and either an operation
+/// (the name of one of the attributes in this crate, followed by its
+/// arguments in parentheses) or `()` to do nothing. The last arm's predicate
+/// can be `_`, which is true if no other predicate is. The arms expand to
+/// mutually exclusive `cfg_attr` attributes, which refer to this crate as
+/// `::add_syntax`:
+///
+/// ```rust
+/// #[add_syntax::select(
+///     feature = "const" => qualify(const),
+///     unix => append_stmts(assert!(value > 0);),
+///     _ => (),
+/// )]
+/// pub fn double(value: u32) -> u32 {
+///     value * 2
+/// }
+/// ```
+///
+/// The code above is equivalent to:
+///
+/// ```rust
+/// #[cfg_attr(
+///     all(feature = "const", not(any())),
+///     add_syntax::qualify(const),
+/// )]
+/// #[cfg_attr(
+///     all(unix, not(any(feature = "const"))),
+///     add_syntax::append_stmts(assert!(value > 0);),
+/// )]
+/// pub fn double(value: u32) -> u32 {
+///     value * 2
+/// }
+/// ```
+///
+/// Only the first true arm applies, even if later predicates are also true.
+/// Here, `const` would be rejected because of the call to `into`:
+///
+/// ```rust
+/// #[add_syntax::select(
+///     feature = "never" => stub(),
+///     all() => set_return(u64),
+///     all() => qualify(const),
+///     _ => stub(),
+/// )]
+/// pub fn widen(value: u32) -> u32 {
+///     value.into()
+/// }
+///
+/// # fn main() {
+/// let wide: u64 = widen(u32::MAX) + 1;
+/// assert_eq!(wide, 1 << 32);
+/// # }
+/// ```
+///
+/// [`cfg`]: https://doc.rust-lang.org/reference/conditional-compilation.html
+#[proc_macro_attribute]
+pub fn select(attr: TokenStream, item: TokenStream) -> TokenStream {
+    select::select(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Applies the operation provided to this attribute to the item to which
+/// this attribute is applied if the compiler is at least the given version.
+///
+/// The version is a string like `"1.61"` or `"1.61.0"`, and the operation is
+/// the name of one of the attributes in this crate, followed by its arguments
+/// in parentheses. The compiler's version is detected by this crate's build
+/// script; if it can't be detected, the operation isn't applied.
+///
+/// ```rust
+/// // `match` in a `const fn` requires Rust 1.46.
+/// #[add_syntax::since("1.46", qualify(const))]
+/// pub fn first(items: &[u8]) -> u8 {
+///     match items {
+///         [first, ..] => *first,
+///         [] => 0,
+///     }
+/// }
+///
+/// const FIRST: u8 = first(&[1, 2]);
+///
+/// # fn main() {
+/// assert_eq!(FIRST, 1);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn since(attr: TokenStream, item: TokenStream) -> TokenStream {
+    version::since(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Applies the operation provided to this attribute to the item to which
+/// this attribute is applied if the compiler is a nightly compiler.
+///
+/// The operation is the name of one of the attributes in this crate,
+/// followed by its arguments in parentheses. See [`since`] for details on
+/// how the compiler is detected.
+///
+/// ```rust
+/// #[add_syntax::nightly(prepend(#[doc = "Built with a nightly compiler."]))]
+/// pub struct Config;
+/// ```
+///
+/// [`since`]: macro@since
+#[proc_macro_attribute]
+pub fn nightly(attr: TokenStream, item: TokenStream) -> TokenStream {
+    version::nightly(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Returns the attribute in this crate named `name`, for attributes that
+/// apply other attributes.
+fn find_macro(
+    name: &str,
+) -> Option<fn(TokenStream, TokenStream) -> TokenStream> {
+    Some(match name {
+        "prepend" => prepend,
+        "insert_after_vis" => insert_after_vis,
+        "qualify" => qualify,
+        "append" => append,
+        "prepend_inner" => prepend_inner,
+        "append_inner" => append_inner,
+        "prepend_stmts" => prepend_stmts,
+        "append_stmts" => append_stmts,
+        "unsafe_body" => unsafe_body,
+        "replace_body" => replace_body,
+        "stub" => stub,
+        "remove" => remove,
+        "replace" => replace,
+        "add_generics" => add_generics,
+        "add_where" => add_where,
+        "add_supertraits" => add_supertraits,
+        "prepend_params" => prepend_params,
+        "append_params" => append_params,
+        "set_return" => set_return,
+        "prepend_fields" => prepend_fields,
+        "append_fields" => append_fields,
+        "prepend_variants" => prepend_variants,
+        "append_variants" => append_variants,
+        "set_vis" => set_vis,
+        "fields_vis" => fields_vis,
+        "rename" => rename,
+        "replace_ident" => replace_ident,
+        "remap_paths" => remap_paths,
+        "wrap" => wrap,
+        "append_impl" => append_impl,
+        "each" => each,
+        "strip_attrs" => strip_attrs,
+        "strip_attrs_recursive" => strip_attrs_recursive,
+        "select" => select,
+        "since" => since,
+        "nightly" => nightly,
+        _ => return None,
+    })
+}
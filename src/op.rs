@@ -0,0 +1,64 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use proc_macro::{Delimiter, Ident, Span, TokenStream, TokenTree};
+
+/// An operation provided to an attribute that applies other attributes,
+/// like the `prepend(unsafe)` in `each(prepend(unsafe))`.
+pub struct Op {
+    /// The name of the attribute to apply.
+    pub name: Ident,
+    /// The arguments to the attribute.
+    pub args: TokenStream,
+    apply: fn(TokenStream, TokenStream) -> TokenStream,
+}
+
+impl Op {
+    /// Parses an operation, which is the name of an attribute in this crate,
+    /// optionally followed by its arguments in parentheses.
+    pub fn parse(trees: &[TokenTree]) -> Result<Self> {
+        let (name, args) = match trees {
+            [TokenTree::Ident(i)] => (i, TokenStream::new()),
+            [TokenTree::Ident(i), TokenTree::Group(g)]
+                if g.delimiter() == Delimiter::Parenthesis =>
+            {
+                (i, g.stream())
+            }
+            _ => {
+                return Err(Error::new(
+                    trees.first().map_or_else(Span::call_site, |t| t.span()),
+                    "expected an operation, like `prepend(...)`",
+                ));
+            }
+        };
+        let apply = crate::find_macro(&name.to_string()).ok_or_else(|| {
+            Error::new(name.span(), format!("unknown operation `{}`", name))
+        })?;
+        Ok(Self {
+            name: name.clone(),
+            args,
+            apply,
+        })
+    }
+
+    /// Applies this operation to `item`.
+    pub fn apply(&self, item: TokenStream) -> TokenStream {
+        (self.apply)(self.args.clone(), item)
+    }
+}
@@ -0,0 +1,286 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use crate::item::{is_ident, is_punct, map_group, split_commas};
+use proc_macro::{Delimiter, Group, Punct, Spacing, TokenStream, TokenTree};
+
+/// A path prefix to replace.
+struct Rule {
+    /// The segments of the prefix to replace.
+    from: Vec<String>,
+    /// The tokens with which to replace the prefix.
+    to: Vec<TokenTree>,
+}
+
+/// Returns the segments of a path like `std::vec`, or [`None`] if `trees`
+/// isn't a simple path.
+fn parse_path(trees: &[TokenTree]) -> Option<Vec<String>> {
+    let trees = if is_sep(trees, 0) {
+        &trees[2..]
+    } else {
+        trees
+    };
+    let mut segments = Vec::new();
+    for (i, tree) in trees.iter().enumerate().step_by(3) {
+        match tree {
+            TokenTree::Ident(ident) => segments.push(ident.to_string()),
+            _ => return None,
+        }
+        if !(i + 1 == trees.len() || is_sep(trees, i + 1)) {
+            return None;
+        }
+    }
+    Some(segments).filter(|s| !s.is_empty())
+}
+
+fn parse_rules(attr: TokenStream) -> Result<Vec<Rule>> {
+    let trees: Vec<_> = attr.into_iter().collect();
+    let mut rules = Vec::new();
+    for rule in split_commas(&trees) {
+        let arrow = rule
+            .windows(2)
+            .position(|w| is_punct(&w[0], '=') && is_punct(&w[1], '>'));
+        let (from, to) = match arrow {
+            Some(i) => (&rule[..i], &rule[i + 2..]),
+            None => return Err(Error::new(rule[0].span(), "expected `=>`")),
+        };
+        let invalid = |trees: &[TokenTree]| {
+            let span = trees.first().unwrap_or(&rule[0]).span();
+            Error::new(span, "expected a path")
+        };
+        let segments = parse_path(from).ok_or_else(|| invalid(from))?;
+        parse_path(to).ok_or_else(|| invalid(to))?;
+        rules.push(Rule {
+            from: segments,
+            to: to.to_vec(),
+        });
+    }
+    if rules.is_empty() {
+        return Err(Error::new(
+            proc_macro::Span::call_site(),
+            "expected `path => path`",
+        ));
+    }
+    // Longer prefixes take precedence.
+    rules.sort_by_key(|r| std::cmp::Reverse(r.from.len()));
+    Ok(rules)
+}
+
+/// Returns whether `trees[i..]` starts with `::`.
+fn is_sep(trees: &[TokenTree], i: usize) -> bool {
+    match trees.get(i..i + 2) {
+        Some([TokenTree::Punct(p), second]) => {
+            p.as_char() == ':'
+                && p.spacing() == Spacing::Joint
+                && is_punct(second, ':')
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether `trees[i]` is the first segment of a path (not counting a
+/// leading `::`).
+fn is_path_start(trees: &[TokenTree], i: usize) -> bool {
+    if !matches!(trees[i], TokenTree::Ident(_)) || i == 0 {
+        return i == 0;
+    }
+    let prev = &trees[i - 1];
+    // Fields, methods, and lifetimes aren't paths.
+    if is_punct(prev, '.') || is_punct(prev, '\'') {
+        return false;
+    }
+    if !(i >= 2 && is_sep(trees, i - 2)) {
+        return true;
+    }
+    // A `::` at the start of a path, like in `::std::mem`.
+    match i.checked_sub(3).map(|j| &trees[j]) {
+        Some(TokenTree::Ident(_)) => false,
+        Some(t) => !is_punct(t, '>'),
+        None => true,
+    }
+}
+
+/// If `trees[start..]` starts with the path segments in `segments`, returns
+/// the index just past them.
+fn match_segments(
+    trees: &[TokenTree],
+    start: usize,
+    segments: &[String],
+) -> Option<usize> {
+    let mut i = start;
+    for (n, segment) in segments.iter().enumerate() {
+        if n > 0 {
+            if !is_sep(trees, i) {
+                return None;
+            }
+            i += 2;
+        }
+        if !trees.get(i).map_or(false, |t| is_ident(t, segment)) {
+            return None;
+        }
+        i += 1;
+    }
+    Some(i)
+}
+
+/// Adds each path in a `use` tree (without the `use` and `;`) to `leaves`,
+/// prefixed by `prefix`.
+fn flatten_use(
+    prefix: Vec<TokenTree>,
+    tree: &[TokenTree],
+    leaves: &mut Vec<Vec<TokenTree>>,
+) {
+    match tree.split_last() {
+        Some((TokenTree::Group(g), path))
+            if g.delimiter() == Delimiter::Brace =>
+        {
+            let mut prefix = prefix;
+            prefix.extend(path.iter().cloned());
+            let inner: Vec<_> = g.stream().into_iter().collect();
+            for tree in split_commas(&inner) {
+                flatten_use(prefix.clone(), tree, leaves);
+            }
+        }
+        _ => {
+            let mut leaf = prefix;
+            leaf.extend(tree.iter().cloned());
+            leaves.push(leaf);
+        }
+    }
+}
+
+/// Remaps a flattened `use` path. Returns [`None`] if no rule applies.
+fn remap_leaf(leaf: &[TokenTree], rules: &[Rule]) -> Option<Vec<TokenTree>> {
+    let start = if is_sep(leaf, 0) {
+        2
+    } else {
+        0
+    };
+    rules.iter().find_map(|rule| {
+        let end = match_segments(leaf, start, &rule.from).filter(|&end| {
+            end == leaf.len()
+                || is_sep(leaf, end)
+                || is_ident(&leaf[end], "as")
+        })?;
+        let mut new = leaf[..start].to_vec();
+        new.extend(rule.to.iter().cloned());
+        new.extend(leaf[end..].iter().cloned());
+        Some(new)
+    })
+}
+
+/// Converts a flattened `use` path back into a valid `use` tree by wrapping
+/// a trailing `self` (as in `std::fmt::self`) in braces.
+fn unflatten_leaf(mut leaf: Vec<TokenTree>) -> Vec<TokenTree> {
+    let len = leaf.len();
+    let self_index = match leaf.get(len.saturating_sub(3)..) {
+        Some([s, r#as, _]) if is_ident(s, "self") && is_ident(r#as, "as") => {
+            len - 3
+        }
+        _ => len.saturating_sub(1),
+    };
+    if self_index >= 2
+        && is_ident(&leaf[self_index], "self")
+        && is_sep(&leaf, self_index - 2)
+    {
+        let inner = leaf.split_off(self_index).into_iter().collect();
+        leaf.push(Group::new(Delimiter::Brace, inner).into());
+    }
+    leaf
+}
+
+/// Remaps the paths in a `use` tree (without the `use` and `;`).
+fn remap_use(tree: &[TokenTree], rules: &[Rule]) -> Vec<TokenTree> {
+    let mut leaves = Vec::new();
+    flatten_use(Vec::new(), tree, &mut leaves);
+    let mut changed = false;
+    let leaves: Vec<_> = leaves
+        .into_iter()
+        .map(|leaf| match remap_leaf(&leaf, rules) {
+            Some(new) => {
+                changed = true;
+                unflatten_leaf(new)
+            }
+            None => unflatten_leaf(leaf),
+        })
+        .collect();
+    if !changed {
+        return tree.to_vec();
+    }
+    if let [leaf] = &leaves[..] {
+        return leaf.clone();
+    }
+    let mut inner = TokenStream::new();
+    for leaf in leaves {
+        inner.extend(leaf);
+        inner.extend([TokenTree::from(Punct::new(',', Spacing::Alone))]);
+    }
+    vec![Group::new(Delimiter::Brace, inner).into()]
+}
+
+fn remap(stream: TokenStream, rules: &[Rule]) -> TokenStream {
+    let trees: Vec<_> = stream.into_iter().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while let Some(tree) = trees.get(i) {
+        // `use<...>` is a precise capturing bound, not a `use` declaration.
+        if is_ident(tree, "use")
+            && !trees.get(i + 1).map_or(false, |t| is_punct(t, '<'))
+        {
+            let end = trees[i..]
+                .iter()
+                .position(|t| is_punct(t, ';'))
+                .map_or(trees.len(), |n| i + n);
+            tokens.push(tree.clone());
+            tokens.extend(remap_use(&trees[i + 1..end], rules));
+            i = end;
+            continue;
+        }
+
+        if is_path_start(&trees, i) {
+            // Single identifiers like `std` are only remapped when they start
+            // a longer path, since they could be variables.
+            let found = rules.iter().find_map(|rule| {
+                match_segments(&trees, i, &rule.from)
+                    .filter(|&end| rule.from.len() > 1 || is_sep(&trees, end))
+                    .map(|end| (rule, end))
+            });
+            if let Some((rule, end)) = found {
+                tokens.extend(rule.to.iter().cloned());
+                i = end;
+                continue;
+            }
+        }
+
+        tokens.push(match tree {
+            TokenTree::Group(g) => map_group(g, |s| remap(s, rules)).into(),
+            tree => tree.clone(),
+        });
+        i += 1;
+    }
+    tokens.into_iter().collect()
+}
+
+pub fn remap_paths(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let rules = parse_rules(attr)?;
+    Ok(remap(item, &rules))
+}
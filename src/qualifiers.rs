@@ -0,0 +1,144 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use crate::item::{Item, is_ident, is_punct};
+use proc_macro::{TokenStream, TokenTree};
+
+/// Qualifier keywords, in the order in which they must appear.
+const KEYWORDS: [&str; 5] = ["default", "const", "async", "unsafe", "extern"];
+const EXTERN: usize = 4;
+
+fn keyword_index(tree: &TokenTree) -> Option<usize> {
+    KEYWORDS.iter().position(|k| is_ident(tree, k))
+}
+
+/// The qualifiers of an item, like `const` and `unsafe`.
+///
+/// Each element holds the tokens of the qualifier at the corresponding index
+/// in [`KEYWORDS`], or is empty if the qualifier isn't present.
+#[derive(Default)]
+pub struct Qualifiers([Vec<TokenTree>; 5]);
+
+impl Qualifiers {
+    /// Parses the qualifiers at the start of `trees` (which should not
+    /// include the item's attributes or visibility). Returns the qualifiers
+    /// and the number of trees they occupy.
+    pub fn parse(trees: &[TokenTree]) -> (Self, usize) {
+        let mut quals = Self::default();
+        let mut i = 0;
+        while let Some(index) = trees.get(i).and_then(keyword_index) {
+            let next = trees.get(i + 1);
+            let is_qualifier = match KEYWORDS[index] {
+                // `const` and `extern` can also introduce items.
+                "const" => next.map_or(false, |t| {
+                    ["async", "unsafe", "extern", "fn"]
+                        .iter()
+                        .any(|k| is_ident(t, k))
+                }),
+                "extern" => !next.map_or(false, |t| is_ident(t, "crate")),
+                "unsafe" => true,
+                _ => matches!(next, Some(TokenTree::Ident(_))),
+            };
+            if !is_qualifier || !quals.0[index].is_empty() {
+                break;
+            }
+            let len = qualifier_len(index, next);
+            quals.0[index].extend(trees[i..i + len].iter().cloned());
+            i += len;
+        }
+        (quals, i)
+    }
+
+    /// Parses a comma-separated list of qualifiers.
+    pub fn parse_list(tokens: TokenStream) -> Result<Self> {
+        let trees: Vec<_> = tokens.into_iter().collect();
+        let mut quals = Self::default();
+        let mut i = 0;
+        while let Some(tree) = trees.get(i) {
+            let index = keyword_index(tree).ok_or_else(|| {
+                Error::new(
+                    tree.span(),
+                    "expected `default`, `const`, `async`, `unsafe`, or \
+                    `extern`",
+                )
+            })?;
+            let len = qualifier_len(index, trees.get(i + 1));
+            quals.0[index] = trees[i..i + len].to_vec();
+            i += len;
+            match trees.get(i) {
+                Some(t) if is_punct(t, ',') => i += 1,
+                Some(t) => return Err(Error::new(t.span(), "expected `,`")),
+                None => {}
+            }
+        }
+        Ok(quals)
+    }
+
+    /// Returns whether the qualifier `keyword` is present.
+    pub fn has(&self, keyword: &str) -> bool {
+        KEYWORDS
+            .iter()
+            .position(|k| *k == keyword)
+            .map_or(false, |i| !self.0[i].is_empty())
+    }
+
+    /// Adds the qualifiers in `other` that aren't already present.
+    pub fn merge(&mut self, other: Self) -> Result<()> {
+        let pairs = self.0.iter_mut().zip(IntoIterator::into_iter(other.0));
+        for (index, (ours, theirs)) in pairs.enumerate() {
+            if theirs.is_empty() {
+                continue;
+            }
+            if index == EXTERN && ours.len() == 2 && theirs.len() == 2 {
+                if ours[1].to_string() != theirs[1].to_string() {
+                    return Err(Error::new(
+                        theirs[1].span(),
+                        "conflicting ABI",
+                    ));
+                }
+                continue;
+            }
+            // A bare `extern` is replaced by one with an explicit ABI.
+            if ours.len() < theirs.len() {
+                *ours = theirs;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn into_tokens(self) -> impl Iterator<Item = TokenTree> {
+        IntoIterator::into_iter(self.0).flatten()
+    }
+}
+
+/// Returns the number of trees occupied by the qualifier whose index in
+/// [`KEYWORDS`] is `index`, where `next` is the tree following the keyword.
+fn qualifier_len(index: usize, next: Option<&TokenTree>) -> usize {
+    match next {
+        Some(TokenTree::Literal(_)) if index == EXTERN => 2,
+        _ => 1,
+    }
+}
+
+pub fn qualify(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let added = Qualifiers::parse_list(attr)?;
+    let mut item = Item::parse(item);
+    item.quals.merge(added)?;
+    Ok(item.into_tokens())
+}
@@ -0,0 +1,200 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use crate::item::{is_ident, is_punct, map_group, split_commas};
+use proc_macro::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
+
+/// Parses `key = [...]` from the start of `trees`, returning the contents of
+/// the brackets and the remaining trees.
+fn parse_arg<'a>(
+    trees: &'a [TokenTree],
+    key: &str,
+) -> Result<(Group, &'a [TokenTree])> {
+    let span = trees.first().map_or_else(Span::call_site, TokenTree::span);
+    match trees {
+        [k, eq, TokenTree::Group(g), rest @ ..]
+            if is_ident(k, key)
+                && is_punct(eq, '=')
+                && g.delimiter() == Delimiter::Bracket =>
+        {
+            Ok((g.clone(), rest))
+        }
+        _ => Err(Error::new(span, format!("expected `{} = [...]`", key))),
+    }
+}
+
+/// Returns whether `trees` matches `pattern`, in which `_` matches any single
+/// tree. The trees matched by `_` are added to `captures`.
+fn matches(
+    trees: &[TokenTree],
+    pattern: &[TokenTree],
+    captures: &mut Vec<TokenTree>,
+) -> bool {
+    if trees.len() != pattern.len() {
+        return false;
+    }
+    let len = captures.len();
+    let all = trees.iter().zip(pattern).all(|pair| match pair {
+        (tree, p) if is_ident(p, "_") => {
+            captures.push(tree.clone());
+            true
+        }
+        (TokenTree::Group(g), TokenTree::Group(p)) => {
+            g.delimiter() == p.delimiter() && {
+                let trees: Vec<_> = g.stream().into_iter().collect();
+                let pattern: Vec<_> = p.stream().into_iter().collect();
+                matches(&trees, &pattern, captures)
+            }
+        }
+        (TokenTree::Punct(t), TokenTree::Punct(p)) => {
+            t.as_char() == p.as_char()
+        }
+        (TokenTree::Ident(t), TokenTree::Ident(p)) => {
+            t.to_string() == p.to_string()
+        }
+        (TokenTree::Literal(t), TokenTree::Literal(p)) => {
+            t.to_string() == p.to_string()
+        }
+        _ => false,
+    });
+    if !all {
+        captures.truncate(len);
+    }
+    all
+}
+
+/// Returns `template` with each `_` replaced by the next tree in `captures`.
+/// Once `captures` is exhausted, `_` is left as is.
+fn substitute(
+    template: TokenStream,
+    captures: &mut impl Iterator<Item = TokenTree>,
+) -> TokenStream {
+    template
+        .into_iter()
+        .map(|tree| match tree {
+            TokenTree::Group(g) => {
+                map_group(&g, |stream| substitute(stream, captures)).into()
+            }
+            tree if is_ident(&tree, "_") => captures.next().unwrap_or(tree),
+            tree => tree,
+        })
+        .collect()
+}
+
+fn replace_in(
+    stream: TokenStream,
+    from: &[TokenTree],
+    to: &TokenStream,
+) -> TokenStream {
+    let trees: Vec<_> = stream.into_iter().collect();
+    let mut tokens = TokenStream::new();
+    let mut i = 0;
+    while let Some(tree) = trees.get(i) {
+        let mut captures = Vec::new();
+        let window = trees.get(i..i + from.len());
+        if window.map_or(false, |w| matches(w, from, &mut captures)) {
+            tokens.extend(substitute(to.clone(), &mut captures.into_iter()));
+            i += from.len();
+            continue;
+        }
+        tokens.extend([match tree {
+            TokenTree::Group(g) => {
+                map_group(g, |stream| replace_in(stream, from, to)).into()
+            }
+            tree => tree.clone(),
+        }]);
+        i += 1;
+    }
+    tokens
+}
+
+pub fn replace(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let trees: Vec<_> = attr.into_iter().collect();
+    let (from, rest) = parse_arg(&trees, "from")?;
+    let rest = match rest {
+        [comma, rest @ ..] if is_punct(comma, ',') => rest,
+        _ => rest,
+    };
+    let (to, rest) = parse_arg(rest, "to")?;
+    match rest {
+        [] => {}
+        [comma] if is_punct(comma, ',') => {}
+        [tree, ..] => return Err(Error::new(tree.span(), "unexpected token")),
+    }
+    let pattern: Vec<_> = from.stream().into_iter().collect();
+    if pattern.is_empty() {
+        return Err(Error::new(from.span(), "expected tokens to replace"));
+    }
+    Ok(replace_in(item, &pattern, &to.stream()))
+}
+
+/// Replaces identifiers in `stream` according to `pairs`. Lifetimes are left
+/// unchanged.
+fn replace_idents(
+    stream: TokenStream,
+    pairs: &[(String, Ident)],
+) -> TokenStream {
+    let mut lifetime = false;
+    stream
+        .into_iter()
+        .map(|tree| {
+            let after_quote = lifetime;
+            lifetime = is_punct(&tree, '\'');
+            match tree {
+                TokenTree::Group(g) => {
+                    map_group(&g, |s| replace_idents(s, pairs)).into()
+                }
+                TokenTree::Ident(old) if !after_quote => {
+                    let name = old.to_string();
+                    match pairs.iter().find(|(from, _)| *from == name) {
+                        Some((_, new)) => {
+                            let mut new = new.clone();
+                            new.set_span(old.span());
+                            new.into()
+                        }
+                        None => old.into(),
+                    }
+                }
+                tree => tree,
+            }
+        })
+        .collect()
+}
+
+pub fn replace_ident(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let trees: Vec<_> = attr.into_iter().collect();
+    let pairs = split_commas(&trees)
+        .into_iter()
+        .map(|pair| match pair {
+            [TokenTree::Ident(from), eq, gt, TokenTree::Ident(to)]
+                if is_punct(eq, '=') && is_punct(gt, '>') =>
+            {
+                Ok((from.to_string(), to.clone()))
+            }
+            _ => Err(Error::new(pair[0].span(), "expected `Old => New`")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if pairs.is_empty() {
+        return Err(Error::new(Span::call_site(), "expected `Old => New`"));
+    }
+    Ok(replace_idents(item, &pairs))
+}
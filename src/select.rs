@@ -0,0 +1,149 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use crate::item::{is_ident, is_punct, split_commas};
+use crate::op::Op;
+use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span};
+use proc_macro::{TokenStream, TokenTree};
+
+/// An arm of [`select`], like `unix => prepend(unsafe)`.
+struct Arm {
+    /// The arm's predicate, or [`None`] for `_`.
+    pred: Option<TokenStream>,
+    /// The operation, or [`None`] for `()`.
+    op: Option<Op>,
+}
+
+impl Arm {
+    fn parse(trees: &[TokenTree]) -> Result<Self> {
+        let arrow = trees.windows(2).position(|w| match &w[0] {
+            TokenTree::Punct(p) => {
+                p.as_char() == '='
+                    && p.spacing() == Spacing::Joint
+                    && is_punct(&w[1], '>')
+            }
+            _ => false,
+        });
+        let arrow = arrow
+            .ok_or_else(|| Error::new(trees[0].span(), "expected `=>`"))?;
+        let (pred, op) = (&trees[..arrow], &trees[arrow + 2..]);
+        let pred = match pred {
+            [] => {
+                return Err(Error::new(
+                    trees[0].span(),
+                    "expected a predicate",
+                ));
+            }
+            [t] if is_ident(t, "_") => None,
+            pred => Some(pred.iter().cloned().collect()),
+        };
+        let op = match op {
+            [TokenTree::Group(g)]
+                if g.delimiter() == Delimiter::Parenthesis
+                    && g.stream().is_empty() =>
+            {
+                None
+            }
+            op => Some(Op::parse(op)?),
+        };
+        Ok(Self {
+            pred,
+            op,
+        })
+    }
+}
+
+fn ident(name: &str) -> TokenTree {
+    Ident::new(name, Span::call_site()).into()
+}
+
+fn punct(c: char, spacing: Spacing) -> TokenTree {
+    Punct::new(c, spacing).into()
+}
+
+fn parens(tokens: TokenStream) -> TokenTree {
+    Group::new(Delimiter::Parenthesis, tokens).into()
+}
+
+/// Returns the predicate `name(preds...)`, like `any(unix, windows)`.
+fn combine<'a>(
+    name: &str,
+    preds: impl IntoIterator<Item = &'a TokenStream>,
+) -> TokenStream {
+    let mut args = TokenStream::new();
+    for pred in preds {
+        args.extend(pred.clone());
+        args.extend([punct(',', Spacing::Alone)]);
+    }
+    IntoIterator::into_iter([ident(name), parens(args)]).collect()
+}
+
+pub fn select(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let trees: Vec<_> = attr.into_iter().collect();
+    let mut arms = Vec::new();
+    for segment in split_commas(&trees) {
+        if arms.last().map_or(false, |a: &Arm| a.pred.is_none()) {
+            return Err(Error::new(
+                segment[0].span(),
+                "`_` must be the last arm",
+            ));
+        }
+        arms.push(Arm::parse(segment)?);
+    }
+
+    let mut tokens = TokenStream::new();
+    let mut prev = Vec::new();
+    for arm in arms {
+        // Each arm applies only if none of the previous arms do.
+        let not_prev = combine("not", [&combine("any", &prev)]);
+        let cond = match &arm.pred {
+            Some(pred) => combine("all", [pred, &not_prev]),
+            None => not_prev,
+        };
+        if let Some(pred) = arm.pred {
+            prev.push(pred);
+        }
+        let op = match arm.op {
+            Some(op) => op,
+            None => continue,
+        };
+        let mut cfg_attr = cond;
+        cfg_attr.extend(IntoIterator::into_iter([
+            punct(',', Spacing::Alone),
+            punct(':', Spacing::Joint),
+            punct(':', Spacing::Alone),
+            ident("add_syntax"),
+            punct(':', Spacing::Joint),
+            punct(':', Spacing::Alone),
+            op.name.into(),
+            parens(op.args),
+        ]));
+        tokens.extend(IntoIterator::into_iter([
+            punct('#', Spacing::Alone),
+            Group::new(
+                Delimiter::Bracket,
+                IntoIterator::into_iter([ident("cfg_attr"), parens(cfg_attr)])
+                    .collect(),
+            )
+            .into(),
+        ]));
+    }
+    tokens.extend(item);
+    Ok(tokens)
+}
@@ -0,0 +1,154 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use crate::item::{Item, find_top_level, is_ident, is_punct, map_group};
+use crate::item::{append_list, prepend_list, skip_generics, split_commas};
+use proc_macro::{Delimiter, Group, Punct, Spacing, TokenStream, TokenTree};
+
+/// Returns the index in [`Item::rest`] of a function's parameter list.
+fn params_index(item: &Item) -> Result<usize> {
+    let not_fn = || Error::new(item.span(), "expected a function");
+    if item.kind().as_deref() != Some("fn") {
+        return Err(not_fn());
+    }
+    let index = skip_generics(&item.rest, item.generics_index().unwrap());
+    match item.rest.get(index) {
+        Some(TokenTree::Group(g))
+            if g.delimiter() == Delimiter::Parenthesis =>
+        {
+            Ok(index)
+        }
+        _ => Err(not_fn()),
+    }
+}
+
+/// Applies `f` to the parameters of a function.
+fn map_params(
+    item: TokenStream,
+    f: impl FnOnce(Vec<TokenTree>) -> Vec<TokenTree>,
+) -> Result<TokenStream> {
+    let mut item = Item::parse(item);
+    let index = params_index(&item)?;
+    if let TokenTree::Group(g) = &item.rest[index] {
+        let group = map_group(g, |stream| {
+            f(stream.into_iter().collect()).into_iter().collect()
+        });
+        item.rest[index] = group.into();
+    }
+    Ok(item.into_tokens())
+}
+
+pub fn prepend_params(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    map_params(item, |mut params| {
+        // Parameters are added after the `self` parameter, if present.
+        let first = split_commas(&params).first().map_or(0, |p| p.len());
+        let pattern_len = params[..first]
+            .iter()
+            .position(|t| is_punct(t, ':'))
+            .unwrap_or(first);
+        let start =
+            if params[..pattern_len].iter().any(|t| is_ident(t, "self")) {
+                first + 1
+            } else {
+                0
+            };
+        let mut rest = params.split_off(start.min(params.len()));
+        prepend_list(&mut rest, attr);
+        if start > 0 {
+            append_list(&mut params, rest.into_iter().collect());
+        } else {
+            params = rest;
+        }
+        params
+    })
+}
+
+pub fn append_params(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    map_params(item, |mut params| {
+        append_list(&mut params, attr);
+        params
+    })
+}
+
+/// Returns `template` with each `_` replaced by `replacement`.
+fn substitute(
+    template: TokenStream,
+    replacement: &[TokenTree],
+) -> Vec<TokenTree> {
+    let mut trees = Vec::new();
+    for tree in template {
+        match tree {
+            TokenTree::Group(g) => trees.push(
+                map_group(&g, |stream| {
+                    substitute(stream, replacement).into_iter().collect()
+                })
+                .into(),
+            ),
+            tree if is_ident(&tree, "_") => {
+                trees.extend(replacement.iter().cloned());
+            }
+            tree => trees.push(tree),
+        }
+    }
+    trees
+}
+
+pub fn set_return(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let mut item = Item::parse(item);
+    let index = params_index(&item)? + 1;
+    let rest = &item.rest;
+    let has_arrow = match rest.get(index..index + 2) {
+        Some([a, b]) => is_punct(a, '-') && is_punct(b, '>'),
+        _ => false,
+    };
+    let end = if has_arrow {
+        find_top_level(rest, index + 2, |t| match t {
+            TokenTree::Group(g) => g.delimiter() == Delimiter::Brace,
+            t => is_ident(t, "where") || is_punct(t, ';'),
+        })
+        .unwrap_or(rest.len())
+    } else {
+        index
+    };
+
+    let unit = [Group::new(Delimiter::Parenthesis, TokenStream::new()).into()];
+    let old = if has_arrow {
+        &rest[index + 2..end]
+    } else {
+        &unit
+    };
+    let new = substitute(attr, old);
+    let mut ret = Vec::new();
+    if !new.is_empty() {
+        ret.push(Punct::new('-', Spacing::Joint).into());
+        ret.push(Punct::new('>', Spacing::Alone).into());
+        ret.extend(new);
+    }
+    item.rest.splice(index..end, ret);
+    Ok(item.into_tokens())
+}
@@ -0,0 +1,330 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use crate::generics::{generics_range, where_range};
+use crate::item::split_commas;
+use crate::item::{Item, find_top_level, is_ident, is_punct, split_attrs};
+use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span};
+use proc_macro::{TokenStream, TokenTree};
+
+/// Returns whether `template` contains the variable `$name`.
+fn contains_var(template: &TokenStream, name: &str) -> bool {
+    let mut dollar = false;
+    template.clone().into_iter().any(|tree| {
+        let after_dollar = dollar;
+        dollar = is_punct(&tree, '$');
+        match tree {
+            TokenTree::Group(g) => contains_var(&g.stream(), name),
+            TokenTree::Ident(i) => after_dollar && i.to_string() == name,
+            _ => false,
+        }
+    })
+}
+
+/// Converts `s` to snake case, like `http_server` for `HTTPServer`.
+fn to_snake(s: &str) -> String {
+    let chars: Vec<_> = s.chars().collect();
+    let mut result = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        let prev = if i > 0 {
+            chars[i - 1]
+        } else {
+            '_'
+        };
+        let next = chars.get(i + 1).copied().unwrap_or('_');
+        // A word starts at an uppercase letter that follows a lowercase
+        // letter or digit, or that precedes a lowercase letter in an acronym.
+        if c.is_uppercase()
+            && prev != '_'
+            && (!prev.is_uppercase() || next.is_lowercase())
+        {
+            result.push('_');
+        }
+        result.extend(c.to_lowercase());
+    }
+    result
+}
+
+/// Converts `s` to camel case, like `HttpServer` for `http_server`.
+fn to_camel(s: &str) -> String {
+    let mut result = String::new();
+    let mut upper = true;
+    for c in s.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Returns the text that a token contributes to a pasted identifier.
+fn paste_text(tree: &TokenTree) -> String {
+    let text = tree.to_string();
+    match tree {
+        // String literals contribute their contents.
+        TokenTree::Literal(_) if text.starts_with('"') => {
+            text.trim_matches('"').into()
+        }
+        _ => text,
+    }
+}
+
+/// Returns the identifier formed by concatenating `pieces`, which are the
+/// contents of `[<...>]`. Each piece may be followed by case conversions,
+/// like `:snake`.
+fn paste(
+    pieces: &[TokenTree],
+    span: Span,
+    lookup: &impl Fn(&str) -> Option<TokenStream>,
+) -> Result<Ident> {
+    let mut ident = String::new();
+    let mut i = 0;
+    while let Some(tree) = pieces.get(i) {
+        i += 1;
+        let mut text = match (tree, pieces.get(i)) {
+            (tree, Some(TokenTree::Ident(name))) if is_punct(tree, '$') => {
+                i += 1;
+                let value = lookup(&name.to_string()).ok_or_else(|| {
+                    Error::new(
+                        name.span(),
+                        format!("unknown variable `{}`", name),
+                    )
+                })?;
+                value.into_iter().map(|t| paste_text(&t)).collect()
+            }
+            (TokenTree::Ident(_), _) | (TokenTree::Literal(_), _) => {
+                paste_text(tree)
+            }
+            (tree, _) => {
+                return Err(Error::new(tree.span(), "unexpected token"));
+            }
+        };
+        while let (true, Some(TokenTree::Ident(case))) = (
+            pieces.get(i).map_or(false, |t| is_punct(t, ':')),
+            pieces.get(i + 1),
+        ) {
+            text = match case.to_string().as_str() {
+                "snake" => to_snake(&text),
+                "camel" => to_camel(&text),
+                "upper" => text.to_uppercase(),
+                "lower" => text.to_lowercase(),
+                _ => {
+                    return Err(Error::new(
+                        case.span(),
+                        "expected `snake`, `camel`, `upper`, or `lower`",
+                    ));
+                }
+            };
+            i += 2;
+        }
+        ident += &text;
+    }
+
+    let mut chars = ident.chars();
+    let valid = chars.next().map_or(false, |c| c == '_' || c.is_alphabetic())
+        && chars.all(|c| c == '_' || c.is_alphanumeric());
+    if !valid {
+        return Err(Error::new(
+            span,
+            format!("`{}` is not a valid identifier", ident),
+        ));
+    }
+    Ok(Ident::new(&ident, span))
+}
+
+/// If `group` is of the form `[<...>]`, returns the trees inside the angle
+/// brackets.
+fn paste_contents(group: &Group) -> Option<Vec<TokenTree>> {
+    if group.delimiter() != Delimiter::Bracket {
+        return None;
+    }
+    let trees: Vec<_> = group.stream().into_iter().collect();
+    match &trees[..] {
+        [first, .., last] if is_punct(first, '<') && is_punct(last, '>') => {
+            Some(trees[1..trees.len() - 1].to_vec())
+        }
+        _ => None,
+    }
+}
+
+/// Replaces each variable `$name` in `template` with `lookup(name)`.
+/// Variables for which `lookup` returns [`None`] are left unchanged.
+///
+/// `[<...>]` is replaced by an identifier formed by concatenating its
+/// contents, as with the `paste` crate.
+pub fn interpolate(
+    template: TokenStream,
+    lookup: &impl Fn(&str) -> Option<TokenStream>,
+) -> Result<TokenStream> {
+    let trees: Vec<_> = template.into_iter().collect();
+    let mut tokens = TokenStream::new();
+    let mut i = 0;
+    while let Some(tree) = trees.get(i) {
+        i += 1;
+        if let (true, Some(TokenTree::Ident(name))) =
+            (is_punct(tree, '$'), trees.get(i))
+        {
+            if let Some(value) = lookup(&name.to_string()) {
+                tokens.extend(value);
+                i += 1;
+                continue;
+            }
+        }
+        tokens.extend([match tree {
+            TokenTree::Group(g) => match paste_contents(g) {
+                Some(pieces) => paste(&pieces, g.span(), lookup)?.into(),
+                None => {
+                    let mut group = Group::new(
+                        g.delimiter(),
+                        interpolate(g.stream(), lookup)?,
+                    );
+                    group.set_span(g.span());
+                    group.into()
+                }
+            },
+            tree => tree.clone(),
+        }]);
+    }
+    Ok(tokens)
+}
+
+/// Returns the argument that refers to a generic parameter, like `'a` for
+/// `'a: 'b` or `T` for `T: Clone = ()`.
+fn generic_arg(param: &[TokenTree]) -> &[TokenTree] {
+    let mut i = 0;
+    // Skip attributes, like `#[may_dangle]`.
+    while param.get(i).map_or(false, |t| is_punct(t, '#')) {
+        i += 2;
+    }
+    let start = match param.get(i) {
+        Some(t) if is_ident(t, "const") => i + 1,
+        _ => i,
+    };
+    let len = match param.get(start) {
+        Some(t) if is_punct(t, '\'') => 2,
+        _ => 1,
+    };
+    param.get(start..start + len).unwrap_or(&[])
+}
+
+/// Joins `segments` with commas.
+fn join_commas<'a>(
+    segments: impl IntoIterator<Item = &'a [TokenTree]>,
+) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    for segment in segments {
+        tokens.extend(segment.iter().cloned());
+        tokens.extend([TokenTree::from(Punct::new(',', Spacing::Alone))]);
+    }
+    tokens
+}
+
+/// Variables that refer to parts of an item.
+pub struct ItemVars {
+    /// `$name`: the item's name.
+    name: Option<TokenTree>,
+    /// `$generics`: the item's generic parameters, without defaults.
+    generics: TokenStream,
+    /// `$generic_args`: the item's generic parameters as arguments.
+    generic_args: TokenStream,
+    /// `$where`: the item's `where` clause.
+    where_clause: TokenStream,
+}
+
+impl ItemVars {
+    pub fn new(item: TokenStream) -> Self {
+        let item = Item::parse(item);
+        let rest = &item.rest;
+        let name = item.name_index().map(|i| rest[i].clone());
+        let (generics, where_clause) =
+            match (generics_range(&item), where_range(&item)) {
+                (Ok(g), Ok(w)) if g.is_empty() => (&[][..], &rest[w]),
+                (Ok(g), Ok(w)) => (&rest[g.start + 1..g.end - 1], &rest[w]),
+                _ => (&[][..], &[][..]),
+            };
+        let params = split_commas(generics);
+        Self {
+            name,
+            // Defaults aren't allowed outside the item's definition.
+            generics: join_commas(params.iter().map(|p| {
+                let end = find_top_level(p, 0, |t| is_punct(t, '='));
+                &p[..end.unwrap_or(p.len())]
+            })),
+            generic_args: join_commas(params.iter().map(|p| generic_arg(p))),
+            where_clause: where_clause.iter().cloned().collect(),
+        }
+    }
+
+    /// Returns an error if `template` uses a variable that isn't available.
+    pub fn check(&self, template: &TokenStream) -> Result<()> {
+        if self.name.is_none() && contains_var(template, "name") {
+            return Err(Error::new(
+                Span::call_site(),
+                "`$name` is not available for this item",
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<TokenStream> {
+        match name {
+            "name" => self.name.clone().map(TokenStream::from),
+            "generics" => Some(self.generics.clone()),
+            "generic_args" => Some(self.generic_args.clone()),
+            "where" => Some(self.where_clause.clone()),
+            _ => None,
+        }
+    }
+}
+
+pub fn append_impl(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream> {
+    let vars = ItemVars::new(item.clone());
+    vars.check(&attr)?;
+    let mut tokens = item;
+    tokens.extend(interpolate(attr, &|name| vars.get(name))?);
+    Ok(tokens)
+}
+
+pub fn wrap(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    if !contains_var(&attr, "item") {
+        return Err(Error::new(Span::call_site(), "expected `$item`"));
+    }
+    let vars = ItemVars::new(item.clone());
+    vars.check(&attr)?;
+    let (attrs, rest) = split_attrs(item.clone());
+    // If `$attrs` is used, `$item` excludes the attributes.
+    let item = if contains_var(&attr, "attrs") {
+        rest
+    } else {
+        item
+    };
+    interpolate(attr, &|name| match name {
+        "item" => Some(item.clone()),
+        "attrs" => Some(attrs.clone()),
+        name => vars.get(name),
+    })
+}
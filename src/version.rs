@@ -0,0 +1,88 @@
+/*
+ * Copyright 2022 taylor.fish <contact@taylor.fish>
+ *
+ * This file is part of add-syntax.
+ *
+ * add-syntax is licensed under the Apache License, Version 2.0
+ * (the "License"); you may not use add-syntax except in compliance
+ * with the License. You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use crate::item::split_commas;
+use crate::op::Op;
+use proc_macro::{Span, TokenStream, TokenTree};
+
+/// Parses a version like `1.61` or `1.61.0`. Missing components are zero.
+fn parse_version(version: &str) -> Option<[u64; 3]> {
+    let mut result = [0; 3];
+    let mut parts = version.split('.');
+    for (i, part) in parts.by_ref().enumerate().take(3) {
+        result[i] = part.parse().ok()?;
+    }
+    match parts.next() {
+        Some(_) => None,
+        None => Some(result),
+    }
+}
+
+/// Returns the version of the compiler, as detected by the build script.
+fn rustc_version() -> Option<[u64; 3]> {
+    option_env!("ADD_SYNTAX_RUSTC_VERSION").and_then(parse_version)
+}
+
+/// Returns whether the compiler is a nightly (or locally built) compiler, as
+/// detected by the build script.
+fn is_nightly() -> bool {
+    matches!(option_env!("ADD_SYNTAX_RUSTC_CHANNEL"), Some("nightly" | "dev"))
+}
+
+pub fn since(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let trees: Vec<_> = attr.into_iter().collect();
+    let args = split_commas(&trees);
+    let (version, op) = match &args[..] {
+        [[TokenTree::Literal(lit)], op] => (lit, op),
+        _ => {
+            let span =
+                trees.first().map_or_else(Span::call_site, |t| t.span());
+            return Err(Error::new(
+                span,
+                "expected a version and an operation, like \
+                `\"1.61\", prepend(const)`",
+            ));
+        }
+    };
+    let text = version.to_string();
+    let required = match text.strip_prefix('"') {
+        Some(text) => parse_version(text.trim_end_matches('"')),
+        None => None,
+    };
+    let required = required.ok_or_else(|| {
+        Error::new(version.span(), "expected a version, like `\"1.61\"`")
+    })?;
+    let op = Op::parse(op)?;
+    // If the version couldn't be detected, the operation isn't applied.
+    if rustc_version().map_or(false, |v| v >= required) {
+        Ok(op.apply(item))
+    } else {
+        Ok(item)
+    }
+}
+
+pub fn nightly(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let trees: Vec<_> = attr.into_iter().collect();
+    let op = Op::parse(&trees)?;
+    if is_nightly() {
+        Ok(op.apply(item))
+    } else {
+        Ok(item)
+    }
+}